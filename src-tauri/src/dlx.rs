@@ -0,0 +1,273 @@
+use array2d::Array2D;
+use bitvec::{order::Lsb0, view::BitView};
+use rand::prelude::*;
+
+use crate::engine::{Backtracker, Solver, Sudoku, Variant};
+
+const ROOT:usize = 0;
+
+/// Exact cover solver based on Knuth's Dancing Links (Algorithm X).
+/// Cage sums and negative constraints are no exact cover constraints, such grids are delegated to the [`Backtracker`].
+pub struct DancingLinks;
+
+// Toroidal doubly linked list of all candidate rows. Node 0 is the root,
+// nodes 1..=column_count are the column headers, the remaining nodes belong to candidate rows.
+struct Matrix {
+    left: Vec<usize>,
+    right: Vec<usize>,
+    up: Vec<usize>,
+    down: Vec<usize>,
+    column: Vec<usize>,
+    row: Vec<usize>,
+    size: Vec<usize>,
+}
+
+impl Matrix {
+    fn new(column_count:usize) -> Matrix {
+        let header_count = column_count + 1;
+        let mut m = Matrix {
+            left: (0..header_count).map(|i| (i + header_count - 1) % header_count).collect(),
+            right: (0..header_count).map(|i| (i + 1) % header_count).collect(),
+            up: (0..header_count).collect(),
+            down: (0..header_count).collect(),
+            column: (0..header_count).collect(),
+            row: vec![0; header_count],
+            size: vec![0; header_count],
+        };
+        m.size[ROOT] = usize::MAX;
+        m
+    }
+
+    fn add_row(&mut self, row_id:usize, columns:&[usize]) {
+        let first = self.left.len();
+        for (i, column_idx) in columns.iter().enumerate() {
+            let c = column_idx + 1;
+            let node = first + i;
+            self.left.push(if i == 0 { first + columns.len() - 1 } else { node - 1 });
+            self.right.push(if i == columns.len() - 1 { first } else { node + 1 });
+            self.up.push(self.up[c]);
+            self.down.push(c);
+            self.column.push(c);
+            self.row.push(row_id);
+
+            let last = self.up[c];
+            self.down[last] = node;
+            self.up[c] = node;
+            self.size[c] += 1;
+        }
+    }
+
+    fn cover(&mut self, c:usize) {
+        self.right[self.left[c]] = self.right[c];
+        self.left[self.right[c]] = self.left[c];
+        let mut i = self.down[c];
+        while i != c {
+            let mut j = self.right[i];
+            while j != i {
+                self.down[self.up[j]] = self.down[j];
+                self.up[self.down[j]] = self.up[j];
+                self.size[self.column[j]] -= 1;
+                j = self.right[j];
+            }
+            i = self.down[i];
+        }
+    }
+
+    fn uncover(&mut self, c:usize) {
+        let mut i = self.up[c];
+        while i != c {
+            let mut j = self.left[i];
+            while j != i {
+                self.size[self.column[j]] += 1;
+                self.down[self.up[j]] = j;
+                self.up[self.down[j]] = j;
+                j = self.left[j];
+            }
+            i = self.up[i];
+        }
+        self.right[self.left[c]] = c;
+        self.left[self.right[c]] = c;
+    }
+
+    // Searches exact covers until `limit` is reached. The first cover found is stored in `first`.
+    fn search(&mut self, partial:&mut Vec<usize>, limit:u8, count:&mut u8, first:&mut Option<Vec<usize>>, rng:&mut Option<StdRng>) {
+        if self.right[ROOT] == ROOT {
+            *count += 1;
+            if first.is_none() {
+                *first = Option::Some(partial.clone());
+            }
+            return;
+        }
+
+        // choose the column with the fewest remaining rows
+        let mut c = self.right[ROOT];
+        let mut j = self.right[c];
+        while j != ROOT {
+            if self.size[j] < self.size[c] {
+                c = j;
+            }
+            j = self.right[j];
+        }
+        if self.size[c] == 0 {
+            return;
+        }
+
+        self.cover(c);
+        let mut rows = Vec::new();
+        let mut r = self.down[c];
+        while r != c {
+            rows.push(r);
+            r = self.down[r];
+        }
+        if let Some(rng_ref) = rng.as_mut() {
+            rows.shuffle(rng_ref);
+        }
+
+        for r in rows {
+            partial.push(self.row[r]);
+            let mut j = self.right[r];
+            while j != r {
+                self.cover(self.column[j]);
+                j = self.right[j];
+            }
+
+            self.search(partial, limit, count, first, rng);
+
+            let mut j = self.left[r];
+            while j != r {
+                self.uncover(self.column[j]);
+                j = self.left[j];
+            }
+            partial.pop();
+
+            if *count >= limit {
+                break;
+            }
+        }
+        self.uncover(c);
+    }
+}
+
+impl DancingLinks {
+    // Every candidate row is encoded as `(row*digits + col)*digits + mov_zero_based`.
+    // Columns are the cell, row-digit, col-digit and quad-digit constraints,
+    // followed by the digit constraints of the extra units (diagonals and windows).
+    fn build(variant:&Variant, values:&Array2D<u8>) -> Result<Matrix, String> {
+        let sudoku = Sudoku::with_variant(variant, Option::Some(values))?;
+        let digits = sudoku.digits;
+        let field_count = digits * digits;
+        let mut m = Matrix::new(4 * field_count + variant.extra_unit_count() * digits);
+        for (r,c,q) in sudoku.fields.iter().copied() {
+            let value = sudoku.values[(r,c)];
+            let moves:Vec<usize> = match value {
+                0 => {
+                    let poss = sudoku.get_possible_mask((r,c,q));
+                    poss.view_bits::<Lsb0>()[0..digits].iter_ones().collect()
+                },
+                _ => vec![(value - 1) as usize],
+            };
+            let extra_units = variant.extra_units(r, c);
+            for mov_zero_based in moves {
+                let mut columns = vec![
+                    r*digits + c,
+                    field_count + r*digits + mov_zero_based,
+                    2*field_count + c*digits + mov_zero_based,
+                    3*field_count + q*digits + mov_zero_based,
+                ];
+                columns.extend(extra_units.iter().map(|unit| 4*field_count + unit*digits + mov_zero_based));
+                m.add_row((r*digits + c)*digits + mov_zero_based, &columns);
+            }
+        }
+        Ok(m)
+    }
+}
+
+impl Solver for DancingLinks {
+    fn solve(&self, variant:&Variant, values:&Array2D<u8>, seed_option:Option<u64>) -> Result<Array2D<u8>, String> {
+        if !variant.is_exact_cover() {
+            return Backtracker.solve(variant, values, seed_option);
+        }
+
+        let mut m = DancingLinks::build(variant, values)?;
+        let digits = variant.size.digits();
+        let mut count = 0;
+        let mut first = Option::None;
+        let mut rng = seed_option.map(StdRng::seed_from_u64);
+        m.search(&mut Vec::new(), 1, &mut count, &mut first, &mut rng);
+
+        first.ok_or("No solution found".into()).map(|rows| {
+            let mut solution = values.clone();
+            for row_id in rows {
+                solution[(row_id / (digits*digits), (row_id / digits) % digits)] = (row_id % digits + 1) as u8;
+            }
+            solution
+        })
+    }
+
+    fn count_solutions(&self, variant:&Variant, values:&Array2D<u8>, limit:u8) -> u8 {
+        if !variant.is_exact_cover() {
+            return Backtracker.count_solutions(variant, values, limit);
+        }
+
+        DancingLinks::build(variant, values).map(|mut m| {
+            let mut count = 0;
+            m.search(&mut Vec::new(), limit, &mut count, &mut Option::None, &mut Option::None);
+            count
+        }).unwrap_or_else(|_| 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_count_solutions() {
+        let rows = vec![
+            vec![0,0,0, 0,0,0, 7,0,9],
+            vec![0,0,0, 0,0,0, 1,0,0],
+            vec![0,0,0, 1,2,0, 0,0,0],
+
+            vec![0,0,0, 0,6,0, 0,0,0],
+            vec![0,0,5, 8,0,0, 2,0,4],
+            vec![0,9,7, 2,0,0, 0,6,5],
+
+            vec![5,0,1, 0,0,2, 0,0,0],
+            vec![0,0,0, 0,0,0, 0,0,0],
+            vec![0,0,0, 0,0,0, 0,0,0],
+        ];
+        let values = Array2D::from_rows(&rows).unwrap();
+
+        use std::time::Instant;
+        let now = Instant::now();
+        assert_eq!(DancingLinks.count_solutions(&Variant::default(), &values, 100), 100);
+
+        let elapsed = now.elapsed();
+        println!("Elapsed: {:.2?}", elapsed);
+
+        assert_eq!(DancingLinks.count_solutions(&Variant::default(), &Array2D::filled_with(0, 9, 9), 2), 2);
+    }
+
+    #[test]
+    fn test_solve() {
+        let values = Array2D::filled_with(0, 9, 9);
+        for seed_option in [Option::None, Option::Some(5), Option::Some(42)] {
+            let solution = DancingLinks.solve(&Variant::default(), &values, seed_option).unwrap();
+            assert!(solution.elements_row_major_iter().all(|i| *i > 0));
+            assert_eq!(Backtracker.count_solutions(&Variant::default(), &solution, 2), 1);
+        }
+        assert_ne!(
+            DancingLinks.solve(&Variant::default(), &values, Option::Some(5)).unwrap(),
+            DancingLinks.solve(&Variant::default(), &values, Option::Some(42)).unwrap(),
+        );
+    }
+
+    #[test]
+    fn test_invalid_grid() {
+        let mut values = Array2D::filled_with(0, 9, 9);
+        values[(0,0)] = 1;
+        values[(0,8)] = 1;
+        assert!(DancingLinks.solve(&Variant::default(), &values, Option::None).is_err());
+        assert_eq!(DancingLinks.count_solutions(&Variant::default(), &values, 2), 0);
+    }
+}
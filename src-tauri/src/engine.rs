@@ -1,9 +1,16 @@
+use std::fmt::Debug;
+use std::sync::{Arc, atomic::{AtomicBool, Ordering}};
+
 use array2d::Array2D;
 use bitvec::{order::Lsb0, view::BitView};
 use rand::prelude::*;
+use serde::{Serialize, Deserialize};
+
+use crate::dlx::DancingLinks;
+use crate::logic::{self, Grade, Rating};
 
-// (row, col, quad) triplets
-const FIELDS:[(usize, usize, usize); 81] = [
+// (row, col, quad) triplets of the classic 9×9 grid
+pub(crate) const FIELDS:[(usize, usize, usize); 81] = [
     (0,0,0), (0,1,0), (0,2,0), (0,3,1), (0,4,1), (0,5,1), (0,6,2), (0,7,2), (0,8,2),
     (1,0,0), (1,1,0), (1,2,0), (1,3,1), (1,4,1), (1,5,1), (1,6,2), (1,7,2), (1,8,2),
     (2,0,0), (2,1,0), (2,2,0), (2,3,1), (2,4,1), (2,5,1), (2,6,2), (2,7,2), (2,8,2),
@@ -14,8 +21,8 @@ const FIELDS:[(usize, usize, usize); 81] = [
     (7,0,6), (7,1,6), (7,2,6), (7,3,7), (7,4,7), (7,5,7), (7,6,8), (7,7,8), (7,8,8),
     (8,0,6), (8,1,6), (8,2,6), (8,3,7), (8,4,7), (8,5,7), (8,6,8), (8,7,8), (8,8,8),
 ];
-const VALUES_BIN:[u16;9] = [1,2,4,8,16,32,64,128,256];
-const VALUES_BIN_INV:[u16;9] = [
+pub(crate) const VALUES_BIN:[u16;16] = [1,2,4,8,16,32,64,128,256,512,1024,2048,4096,8192,16384,32768];
+const VALUES_BIN_INV:[u16;16] = [
     0b1111111111111110,
     0b1111111111111101,
     0b1111111111111011,
@@ -25,28 +32,785 @@ const VALUES_BIN_INV:[u16;9] = [
     0b1111111110111111,
     0b1111111101111111,
     0b1111111011111111,
+    0b1111110111111111,
+    0b1111101111111111,
+    0b1111011111111111,
+    0b1110111111111111,
+    0b1101111111111111,
+    0b1011111111111111,
+    0b0111111111111111,
 ];
+const MAX_DIGITS:usize = 16;
+const MAX_RATED_ATTEMPTS:u64 = 100;
+const MAX_REGION_ATTEMPTS:u64 = 100;
+const MIN_CAGE_SIZE:usize = 2;
+const MAX_CAGE_SIZE:usize = 5;
+const REGION_SWAPS_PER_FIELD:usize = 10;
+const KNIGHT_MOVES:[(isize, isize); 8] = [(-2,-1), (-2,1), (-1,-2), (-1,2), (1,-2), (1,2), (2,-1), (2,1)];
+const KING_MOVES:[(isize, isize); 8] = [(-1,-1), (-1,0), (-1,1), (0,-1), (0,1), (1,-1), (1,0), (1,1)];
+const ALL_DIGITS:u16 = 0b1111111111111111;
+
+
+/// A solving backend for [`solve_with`], [`count_solutions_with`] and [`generate_monitored`].
+pub trait Solver {
+    /// Solves a given grid under the rules of the `variant`. If `seed` is provided, solve is performed randomly.
+    fn solve(&self, variant:&Variant, values:&Array2D<u8>, seed_option:Option<u64>) -> Result<Array2D<u8>, String>;
+
+    /// Counts solutions under the rules of the `variant` up to a maximum of `limit`.
+    fn count_solutions(&self, variant:&Variant, values:&Array2D<u8>, limit:u8) -> u8;
+}
+
+/// Bitmask backtracking over all fields in row-major order.
+/// Grids with cages, negative constraints or irregular regions are filled in order of the most constrained field instead.
+pub struct Backtracker;
+
+/// Solving algorithm used by the engine, Dancing Links by default. Random solves differ between the backends,
+/// but generated grids don't: their solution grid is always drawn by the [`Backtracker`], so a seed generates
+/// the same grid with every backend.
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq, Default)]
+pub enum Backend {
+    Backtracking,
+    #[default]
+    DancingLinks,
+}
+
+impl Backend {
+    pub fn solver(&self) -> &'static dyn Solver {
+        match self {
+            Backend::Backtracking => &Backtracker,
+            Backend::DancingLinks => &DancingLinks,
+        }
+    }
+}
+
+/// Symmetry of the clue pattern of generated grids.
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq, Default)]
+pub enum Symmetry {
+    #[default]
+    None,
+    Rotational180,
+    Rotational90,
+    MirrorHorizontal,
+    MirrorVertical,
+    Diagonal,
+    AntiDiagonal,
+}
+
+impl Symmetry {
+    // all fields of a grid with `digits` rows and cols that have to be removed together with the given field
+    fn orbit(&self, r:usize, c:usize, digits:usize) -> Vec<(usize, usize)> {
+        let m = digits - 1;
+        let mut orbit = match self {
+            Symmetry::None => vec![(r,c)],
+            Symmetry::Rotational180 => vec![(r,c), (m-r, m-c)],
+            Symmetry::Rotational90 => vec![(r,c), (c, m-r), (m-r, m-c), (m-c, r)],
+            Symmetry::MirrorHorizontal => vec![(r,c), (m-r, c)],
+            Symmetry::MirrorVertical => vec![(r,c), (r, m-c)],
+            Symmetry::Diagonal => vec![(r,c), (c,r)],
+            Symmetry::AntiDiagonal => vec![(r,c), (m-c, m-r)],
+        };
+        orbit.sort();
+        orbit.dedup();
+        orbit
+    }
+}
+
+/// A killer cage: its fields hold distinct digits that add up to `sum`.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct Cage {
+    pub cells: Vec<(usize, usize)>,
+    pub sum: u8,
+}
+
+/// Dimensions of a quad. A grid has as many rows, cols and digits as a quad has fields,
+/// e.g. 2×3 quads make up a 6×6 grid.
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Size {
+    pub box_rows: usize,
+    pub box_cols: usize,
+}
+
+impl Default for Size {
+    fn default() -> Size {
+        Size { box_rows: 3, box_cols: 3 }
+    }
+}
+
+impl Size {
+    pub fn new(box_rows:usize, box_cols:usize) -> Result<Size, String> {
+        let size = Size { box_rows, box_cols };
+        size.validate().map(|_| size)
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.box_rows < 2 || self.box_cols < 2 || self.digits() > MAX_DIGITS {
+            return Err(format!("Quads must be at least 2×2 with at most {} fields", MAX_DIGITS));
+        }
+        Ok(())
+    }
+
+    /// Number of rows, cols and digits of the grid.
+    pub fn digits(&self) -> usize {
+        self.box_rows * self.box_cols
+    }
+
+    pub fn quad(&self, r:usize, c:usize) -> usize {
+        (r / self.box_rows) * self.box_rows + c / self.box_cols
+    }
+}
+
+/// Rules on top of the classic row, col and quad constraints.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq, Default)]
+pub struct Variant {
+    #[serde(default)]
+    pub size: Size,
+    #[serde(default)]
+    pub cages: Vec<Cage>,
+    // region index of every field of a Jigsaw Sudoku, row by row. Without a map, the quads are the regions.
+    #[serde(default)]
+    pub regions: Option<Vec<Vec<usize>>>,
+    // both main diagonals hold every digit once (X-Sudoku)
+    #[serde(default)]
+    pub diagonals: bool,
+    // the hyper boxes between the quads hold every digit once (Windoku)
+    #[serde(default)]
+    pub windows: bool,
+    // no equal digits a chess knight's move apart
+    #[serde(default)]
+    pub anti_knight: bool,
+    // no equal digits a chess king's move apart
+    #[serde(default)]
+    pub anti_king: bool,
+    // no consecutive digits in orthogonally adjacent fields
+    #[serde(default)]
+    pub non_consecutive: bool,
+}
+
+impl Variant {
+    #[cfg(test)]
+    pub fn killer(cages:Vec<Cage>) -> Variant {
+        Variant { cages, ..Variant::default() }
+    }
+
+    pub fn sized(size:Size) -> Variant {
+        Variant { size, ..Variant::default() }
+    }
+
+    #[cfg(test)]
+    pub fn jigsaw(regions:Vec<Vec<usize>>) -> Variant {
+        Variant { regions: Option::Some(regions), ..Variant::default() }
+    }
+
+    /// Region of a field, which is its quad unless a region map is set.
+    pub fn region(&self, r:usize, c:usize) -> usize {
+        match self.regions.as_ref() {
+            Some(regions) => regions[r][c],
+            None => self.size.quad(r, c),
+        }
+    }
+
+    /// Builds the constraints of all rules, starting with the classic rows, cols and regions.
+    pub fn constraints(&self) -> Vec<Box<dyn Constraint>> {
+        let mut constraints:Vec<Box<dyn Constraint>> = vec![
+            Box::new(Units::rows(self)),
+            Box::new(Units::cols(self)),
+            Box::new(Units::regions(self)),
+        ];
+        if self.extra_unit_count() > 0 {
+            constraints.push(Box::new(Units::extra(self)));
+        }
+        if self.anti_knight || self.anti_king {
+            constraints.push(Box::new(Peers::new(self)));
+        }
+        if self.non_consecutive {
+            constraints.push(Box::new(NonConsecutive::new(self)));
+        }
+        if !self.cages.is_empty() {
+            constraints.push(Box::new(Cages::new(self)));
+        }
+        constraints
+    }
+
+    /// Checks whether all rules are units holding every digit once, as opposed to cages and negative constraints.
+    pub fn is_exact_cover(&self) -> bool {
+        self.cages.is_empty() && !self.anti_knight && !self.anti_king && !self.non_consecutive
+    }
+
+    // Row-major backtracking is only fast enough for exact covers of quads. Random solves of irregular regions
+    // run into dead ends late in the grid, and proving a region map unsolvable takes practically forever.
+    fn is_row_major(&self) -> bool {
+        self.is_exact_cover() && self.regions.is_none()
+    }
+
+    /// Fields that must not hold the same digit as the given one because of the anti-knight and anti-king rules.
+    pub fn peers(&self, r:usize, c:usize) -> Vec<(usize, usize)> {
+        let digits = self.size.digits() as isize;
+        let mut moves:Vec<(isize, isize)> = Vec::new();
+        if self.anti_knight {
+            moves.extend(KNIGHT_MOVES);
+        }
+        if self.anti_king {
+            moves.extend(KING_MOVES);
+        }
+        moves.into_iter()
+            .map(|(dr,dc)| (r as isize + dr, c as isize + dc))
+            .filter(|(r,c)| *r >= 0 && *r < digits && *c >= 0 && *c < digits)
+            .map(|(r,c)| (r as usize, c as usize))
+            .collect()
+    }
+
+    /// Fields that must not hold a digit consecutive to the given one because of the non-consecutive rule.
+    pub fn adjacent(&self, r:usize, c:usize) -> Vec<(usize, usize)> {
+        match self.non_consecutive {
+            true => neighbours(r, c, self.size.digits()),
+            false => Vec::new(),
+        }
+    }
+
+    /// Number of extra units of the enabled rules, see [`Variant::extra_units`].
+    pub fn extra_unit_count(&self) -> usize {
+        self.window_offset() + match self.windows {
+            true => self.window_rows() * self.window_cols(),
+            false => 0,
+        }
+    }
+
+    /// Extra units of the enabled rules a field belongs to.
+    /// With diagonals, units 0 and 1 are the main and anti diagonal. The windows follow row by row.
+    pub fn extra_units(&self, r:usize, c:usize) -> Vec<usize> {
+        let digits = self.size.digits();
+        let mut units = Vec::new();
+        if self.diagonals {
+            if r == c {
+                units.push(0);
+            }
+            if r + c + 1 == digits {
+                units.push(1);
+            }
+        }
+        if self.windows {
+            // windows are quad-sized and separated from the border and each other by one field
+            let (box_rows, box_cols) = (self.size.box_rows, self.size.box_cols);
+            let (window_row, window_col) = (r / (box_rows + 1), c / (box_cols + 1));
+            if !r.is_multiple_of(box_rows + 1) && !c.is_multiple_of(box_cols + 1) && window_row < self.window_rows() && window_col < self.window_cols() {
+                units.push(self.window_offset() + window_row * self.window_cols() + window_col);
+            }
+        }
+        units
+    }
 
+    fn window_offset(&self) -> usize {
+        match self.diagonals {
+            true => 2,
+            false => 0,
+        }
+    }
+
+    fn window_rows(&self) -> usize {
+        self.size.digits() / (self.size.box_rows + 1)
+    }
+
+    fn window_cols(&self) -> usize {
+        self.size.digits() / (self.size.box_cols + 1)
+    }
+
+    // Fields of the rows, cols, regions and extra units, which hold every digit exactly once
+    fn units(&self) -> Vec<Vec<(usize, usize, usize)>> {
+        let fields = self.fields();
+        let digits = self.size.digits();
+        let mut units = vec![Vec::new(); 3*digits + self.extra_unit_count()];
+        for rcq in fields {
+            let (r,c,q) = rcq;
+            units[r].push(rcq);
+            units[digits + c].push(rcq);
+            units[2*digits + q].push(rcq);
+            self.extra_units(r, c).into_iter().for_each(|unit| units[3*digits + unit].push(rcq));
+        }
+        units
+    }
+
+    /// All `(row, col, region)` triplets in row-major order.
+    pub fn fields(&self) -> Vec<(usize, usize, usize)> {
+        let digits = self.size.digits();
+        (0..digits * digits).map(|idx| (idx / digits, idx % digits, self.region(idx / digits, idx % digits))).collect()
+    }
+
+    /// Checks for a 9×9 grid without additional rules.
+    pub fn is_classic(&self) -> bool {
+        *self == Variant::default()
+    }
+
+    /// Checks the size, the region map, that cages don't overlap and their sums can be reached with distinct digits.
+    pub fn validate(&self) -> Result<(), String> {
+        self.size.validate()?;
+        let digits = self.size.digits();
+        if let Some(regions) = self.regions.as_ref() {
+            validate_regions(regions, digits)?;
+        }
+        let mut covered = vec![false; digits * digits];
+        for cage in self.cages.iter() {
+            if cage.cells.is_empty() || cage.cells.len() > digits {
+                return Err(format!("A cage must consist of 1 to {} fields", digits));
+            }
+            for (r,c) in cage.cells.iter() {
+                if *r >= digits || *c >= digits {
+                    return Err("Cage field is out of the grid".into());
+                }
+                if covered[r*digits + c] {
+                    return Err("Cages must not overlap".into());
+                }
+                covered[r*digits + c] = true;
+            }
+            let size = cage.cells.len();
+            let min_sum = size * (size + 1) / 2;
+            let max_sum = size * (2*digits + 1 - size) / 2;
+            if (cage.sum as usize) < min_sum || cage.sum as usize > max_sum {
+                return Err(format!("Cage sum {} can't be reached with {} distinct digits", cage.sum, size));
+            }
+        }
+        Ok(())
+    }
+}
 
-#[derive(Debug)]
-struct Sudoku {
-    values: Array2D<u8>,
-    poss_rows: [u16; 9],
-    poss_cols: [u16; 9],
-    poss_quads: [u16; 9],
+// Every region must be a contiguous area of exactly `digits` fields
+fn validate_regions(regions:&[Vec<usize>], digits:usize) -> Result<(), String> {
+    if regions.len() != digits || regions.iter().any(|row| row.len() != digits) {
+        return Err(format!("A region map must consist of {} rows and cols", digits));
+    }
+    let mut counts = vec![0; digits];
+    for region in regions.iter().flatten() {
+        if *region >= digits {
+            return Err("Region index is out of range".into());
+        }
+        counts[*region] += 1;
+    }
+    if counts.iter().any(|count| *count != digits) {
+        return Err(format!("Every region must consist of exactly {} fields", digits));
+    }
+    match (0..digits).find(|region| !is_contiguous(regions, *region)) {
+        Some(region) => Err(format!("Region {} is not contiguous", region + 1)),
+        None => Ok(()),
+    }
+}
+
+// flood fill from the first field of the region
+fn is_contiguous(regions:&[Vec<usize>], region:usize) -> bool {
+    let digits = regions.len();
+    let fields:Vec<(usize, usize)> = (0..digits * digits)
+        .map(|idx| (idx / digits, idx % digits))
+        .filter(|(r,c)| regions[*r][*c] == region)
+        .collect();
+    let mut reached = vec![false; digits * digits];
+    let mut stack:Vec<(usize, usize)> = fields.first().into_iter().copied().collect();
+    let mut reached_count = 0;
+    while let Some((r,c)) = stack.pop() {
+        if reached[r*digits + c] {
+            continue;
+        }
+        reached[r*digits + c] = true;
+        reached_count += 1;
+        stack.extend(neighbours(r, c, digits).into_iter().filter(|(r,c)| regions[*r][*c] == region));
+    }
+    reached_count == fields.len()
+}
+
+// Running state of a cage while solving
+#[derive(Debug, Clone)]
+struct CageState {
+    sum: u8,
+    size: u8,
+    total: u8,
+    filled: u8,
+    used: u16,
+}
+
+impl CageState {
+    // digits that can still be placed without making the cage sum unreachable
+    fn poss(&self, digits:usize) -> u16 {
+        let mut poss = 0u16;
+        if self.filled == self.size {
+            return poss;
+        }
+        let others = self.size - self.filled - 1;
+
+        for (mov_zero_based, mov_bin) in VALUES_BIN[0..digits].iter().copied().enumerate() {
+            if self.used & mov_bin > 0 {
+                continue;
+            }
+            let value = (mov_zero_based + 1) as u8;
+            if self.total + value > self.sum {
+                break;
+            }
+
+            // the other remaining fields need distinct unused digits adding up to the rest
+            let rest = self.sum - self.total - value;
+            let unused = self.used | mov_bin;
+            let (mut min, mut max, mut count) = (0u8, 0u8, 0u8);
+            for (other, other_bin) in VALUES_BIN[0..digits].iter().enumerate() {
+                if count < others && unused & other_bin == 0 {
+                    min += (other + 1) as u8;
+                    count += 1;
+                }
+            }
+            if count < others {
+                continue;
+            }
+            count = 0;
+            for (other, other_bin) in VALUES_BIN[0..digits].iter().enumerate().rev() {
+                if count < others && unused & other_bin == 0 {
+                    max += (other + 1) as u8;
+                    count += 1;
+                }
+            }
+            if rest >= min && rest <= max {
+                poss |= mov_bin;
+            }
+        }
+        poss
+    }
+}
+
+/// A rule of a variant. The solver asks every constraint for the candidates of a field
+/// and informs it about placed digits, games ask it for conflicts of a field.
+pub trait Constraint: Debug + Send + Sync {
+    /// Removes the digits from the candidates `poss` of an empty field that would break the rule.
+    fn filter(&self, rc:(usize, usize), values:&Array2D<u8>, poss:u16) -> u16;
+
+    /// Updates the state of the rule after a digit has been placed in a field.
+    fn place(&mut self, _rc:(usize, usize), _mov_zero_based:usize) {}
+
+    /// Reverts [`Constraint::place`] after the digit has been removed from the field.
+    fn unplace(&mut self, _rc:(usize, usize), _mov_zero_based:usize) {}
+
+    /// Checks whether the value of a filled field breaks the rule.
+    fn conflicts(&self, rc:(usize, usize), values:&Array2D<u8>) -> bool;
+
+    fn box_clone(&self) -> Box<dyn Constraint>;
+}
+
+impl Clone for Box<dyn Constraint> {
+    fn clone(&self) -> Box<dyn Constraint> {
+        self.box_clone()
+    }
+}
+
+/// Units holding every digit at most once, like the rows, cols and regions of the classic rules.
+#[derive(Debug, Clone)]
+pub struct Units {
+    digits: usize,
+    // units of every field in row-major order
+    units_of: Vec<Vec<usize>>,
+    // fields of every unit
+    fields_of: Vec<Vec<(usize, usize)>>,
+    // digits that are still free in every unit
+    poss: Vec<u16>,
+}
+
+impl Units {
+    /// Builds `count` units from the units every field belongs to.
+    pub fn new(digits:usize, count:usize, units:impl Fn(usize, usize) -> Vec<usize>) -> Units {
+        let units_of:Vec<Vec<usize>> = (0..digits * digits).map(|idx| units(idx / digits, idx % digits)).collect();
+        let mut fields_of = vec![Vec::new(); count];
+        units_of.iter().enumerate().for_each(|(idx, field_units)| {
+            field_units.iter().for_each(|unit| fields_of[*unit].push((idx / digits, idx % digits)));
+        });
+        Units { digits, units_of, fields_of, poss: vec![ALL_DIGITS; count] }
+    }
+
+    pub fn rows(variant:&Variant) -> Units {
+        let digits = variant.size.digits();
+        Units::new(digits, digits, |r,_| vec![r])
+    }
+
+    pub fn cols(variant:&Variant) -> Units {
+        let digits = variant.size.digits();
+        Units::new(digits, digits, |_,c| vec![c])
+    }
+
+    pub fn regions(variant:&Variant) -> Units {
+        let digits = variant.size.digits();
+        Units::new(digits, digits, |r,c| vec![variant.region(r, c)])
+    }
+
+    /// Diagonals and windows, see [`Variant::extra_units`].
+    pub fn extra(variant:&Variant) -> Units {
+        Units::new(variant.size.digits(), variant.extra_unit_count(), |r,c| variant.extra_units(r, c))
+    }
+}
+
+impl Constraint for Units {
+    fn filter(&self, rc:(usize, usize), _values:&Array2D<u8>, poss:u16) -> u16 {
+        self.units_of[rc.0*self.digits + rc.1].iter().fold(poss, |poss, unit| poss & self.poss[*unit])
+    }
+
+    fn place(&mut self, rc:(usize, usize), mov_zero_based:usize) {
+        for unit in self.units_of[rc.0*self.digits + rc.1].iter() {
+            self.poss[*unit] &= VALUES_BIN_INV[mov_zero_based];
+        }
+    }
+
+    fn unplace(&mut self, rc:(usize, usize), mov_zero_based:usize) {
+        for unit in self.units_of[rc.0*self.digits + rc.1].iter() {
+            self.poss[*unit] |= VALUES_BIN[mov_zero_based];
+        }
+    }
+
+    fn conflicts(&self, rc:(usize, usize), values:&Array2D<u8>) -> bool {
+        self.units_of[rc.0*self.digits + rc.1].iter()
+            .flat_map(|unit| self.fields_of[*unit].iter())
+            .any(|other| *other != rc && values[*other] == values[rc])
+    }
+
+    fn box_clone(&self) -> Box<dyn Constraint> {
+        Box::new(self.clone())
+    }
+}
+
+/// Fields a chess knight's or king's move apart must not hold the same digit.
+#[derive(Debug, Clone)]
+pub struct Peers {
+    digits: usize,
+    // peers of every field in row-major order
+    peers_of: Vec<Vec<(usize, usize)>>,
+}
+
+impl Peers {
+    pub fn new(variant:&Variant) -> Peers {
+        let digits = variant.size.digits();
+        Peers { digits, peers_of: (0..digits * digits).map(|idx| variant.peers(idx / digits, idx % digits)).collect() }
+    }
+}
+
+impl Constraint for Peers {
+    fn filter(&self, rc:(usize, usize), values:&Array2D<u8>, poss:u16) -> u16 {
+        self.peers_of[rc.0*self.digits + rc.1].iter().fold(poss, |poss, peer| match values[*peer] as usize {
+            0 => poss,
+            value => poss & VALUES_BIN_INV[value - 1],
+        })
+    }
+
+    fn conflicts(&self, rc:(usize, usize), values:&Array2D<u8>) -> bool {
+        self.peers_of[rc.0*self.digits + rc.1].iter().any(|peer| values[*peer] == values[rc])
+    }
+
+    fn box_clone(&self) -> Box<dyn Constraint> {
+        Box::new(self.clone())
+    }
+}
+
+/// Orthogonally adjacent fields must not hold consecutive digits.
+#[derive(Debug, Clone)]
+pub struct NonConsecutive {
+    digits: usize,
+    // orthogonal neighbours of every field in row-major order
+    adjacent_of: Vec<Vec<(usize, usize)>>,
+}
+
+impl NonConsecutive {
+    pub fn new(variant:&Variant) -> NonConsecutive {
+        let digits = variant.size.digits();
+        NonConsecutive { digits, adjacent_of: (0..digits * digits).map(|idx| variant.adjacent(idx / digits, idx % digits)).collect() }
+    }
+}
+
+impl Constraint for NonConsecutive {
+    fn filter(&self, rc:(usize, usize), values:&Array2D<u8>, poss:u16) -> u16 {
+        let mut poss = poss;
+        for adjacent in self.adjacent_of[rc.0*self.digits + rc.1].iter() {
+            let value = values[*adjacent] as usize;
+            if value > 1 {
+                poss &= VALUES_BIN_INV[value - 2];
+            }
+            if value > 0 && value < self.digits {
+                poss &= VALUES_BIN_INV[value];
+            }
+        }
+        poss
+    }
+
+    fn conflicts(&self, rc:(usize, usize), values:&Array2D<u8>) -> bool {
+        self.adjacent_of[rc.0*self.digits + rc.1].iter().any(|adjacent| values[*adjacent].abs_diff(values[rc]) == 1)
+    }
+
+    fn box_clone(&self) -> Box<dyn Constraint> {
+        Box::new(self.clone())
+    }
+}
+
+/// Killer cages: their fields hold distinct digits that add up to the cage sum.
+#[derive(Debug, Clone)]
+pub struct Cages {
+    digits: usize,
+    cages: Vec<Cage>,
+    states: Vec<CageState>,
+    // cage index of every field in row-major order
+    cage_of: Vec<Option<usize>>,
+}
+
+impl Cages {
+    pub fn new(variant:&Variant) -> Cages {
+        let digits = variant.size.digits();
+        let mut cage_of = vec![Option::None; digits * digits];
+        variant.cages.iter().enumerate().for_each(|(cage_idx, cage)| {
+            cage.cells.iter().for_each(|(r,c)| cage_of[r*digits + c] = Option::Some(cage_idx));
+        });
+        Cages {
+            digits,
+            cages: variant.cages.clone(),
+            states: variant.cages.iter().map(|cage| CageState {
+                sum: cage.sum,
+                size: cage.cells.len() as u8,
+                total: 0,
+                filled: 0,
+                used: 0,
+            }).collect(),
+            cage_of,
+        }
+    }
+}
+
+impl Constraint for Cages {
+    fn filter(&self, rc:(usize, usize), _values:&Array2D<u8>, poss:u16) -> u16 {
+        match self.cage_of[rc.0*self.digits + rc.1] {
+            Some(cage_idx) => poss & self.states[cage_idx].poss(self.digits),
+            None => poss,
+        }
+    }
+
+    fn place(&mut self, rc:(usize, usize), mov_zero_based:usize) {
+        if let Some(cage_idx) = self.cage_of[rc.0*self.digits + rc.1] {
+            let state = &mut self.states[cage_idx];
+            state.total += (mov_zero_based + 1) as u8;
+            state.filled += 1;
+            state.used |= VALUES_BIN[mov_zero_based];
+        }
+    }
+
+    fn unplace(&mut self, rc:(usize, usize), mov_zero_based:usize) {
+        if let Some(cage_idx) = self.cage_of[rc.0*self.digits + rc.1] {
+            let state = &mut self.states[cage_idx];
+            state.total -= (mov_zero_based + 1) as u8;
+            state.filled -= 1;
+            state.used &= VALUES_BIN_INV[mov_zero_based];
+        }
+    }
+
+    // A cage is violated by repeated digits, by exceeding its sum or by missing it once complete
+    fn conflicts(&self, rc:(usize, usize), values:&Array2D<u8>) -> bool {
+        match self.cage_of[rc.0*self.digits + rc.1] {
+            Some(cage_idx) => {
+                let cage = &self.cages[cage_idx];
+                let cage_values:Vec<u8> = cage.cells.iter().map(|cell| values[*cell]).collect();
+                let total:u16 = cage_values.iter().map(|value| *value as u16).sum();
+                let is_complete = cage_values.iter().all(|value| *value > 0);
+                cage_values.iter().filter(|value| **value == values[rc]).count() > 1
+                    || total > cage.sum as u16
+                    || (is_complete && total != cage.sum as u16)
+            },
+            None => false,
+        }
+    }
+
+    fn box_clone(&self) -> Box<dyn Constraint> {
+        Box::new(self.clone())
+    }
+}
+
+/// Cancellation token shared between a running generation and its owner.
+#[derive(Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    pub fn is_same(&self, other:&CancellationToken) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+/// Progress of a running generation, reported on every uniqueness check.
+#[derive(Clone, Copy, Serialize, Debug)]
+pub struct Progress {
+    pub attempts: u32,
+    pub removed_count: u16,
+}
+
+/// Observes a running generation: reports its progress and allows to cancel it.
+pub struct Monitor<'a> {
+    token: CancellationToken,
+    on_progress: Box<dyn FnMut(Progress) + 'a>,
+    attempts: u32,
+}
+
+impl<'a> Monitor<'a> {
+    pub fn new(token:CancellationToken, on_progress:impl FnMut(Progress) + 'a) -> Monitor<'a> {
+        Monitor {
+            token,
+            on_progress: Box::new(on_progress),
+            attempts: 0,
+        }
+    }
+
+    #[cfg(test)]
+    pub fn unobserved() -> Monitor<'a> {
+        Monitor::new(CancellationToken::default(), |_| {})
+    }
+
+    // returns false if the generation should stop
+    fn report(&mut self, removed_count:u16) -> bool {
+        self.attempts += 1;
+        (self.on_progress)(Progress {
+            attempts: self.attempts,
+            removed_count,
+        });
+        !self.token.is_cancelled()
+    }
+}
+
+// a zero-based move in the field of a `(row, col, region)` triplet
+type Placement = ((usize, usize, usize), usize);
+
+#[derive(Debug, Clone)]
+pub(crate) struct Sudoku {
+    pub(crate) values: Array2D<u8>,
+    pub(crate) digits: usize,
+    // all (row, col, region) triplets in row-major order
+    pub(crate) fields: Vec<(usize, usize, usize)>,
+    // fields of every unit holding each digit exactly once
+    units: Vec<Vec<(usize, usize, usize)>>,
+    variant: Variant,
+    constraints: Vec<Box<dyn Constraint>>,
 }
 
 impl Sudoku {
-    fn new(values_option: Option<&Array2D<u8>>) -> Result<Sudoku, String> {
+    pub(crate) fn new(values_option: Option<&Array2D<u8>>) -> Result<Sudoku, String> {
+        Sudoku::with_variant(&Variant::default(), values_option)
+    }
+
+    pub(crate) fn with_variant(variant:&Variant, values_option: Option<&Array2D<u8>>) -> Result<Sudoku, String> {
+        variant.validate()?;
+        let digits = variant.size.digits();
+        if values_option.is_some_and(|values| values.num_rows() != digits || values.num_columns() != digits) {
+            return Err("Grid size doesn't match the variant".into());
+        }
+
         let mut s = Sudoku {
-            values: Array2D::filled_with(0, 9, 9),
-            poss_rows: [0b1111111111111111u16; 9],
-            poss_cols: [0b1111111111111111u16; 9],
-            poss_quads: [0b1111111111111111u16; 9],
+            values: Array2D::filled_with(0, digits, digits),
+            digits,
+            fields: variant.fields(),
+            units: variant.units(),
+            variant: variant.clone(),
+            constraints: variant.constraints(),
         };
         match values_option {
             Some(values) => values.elements_row_major_iter().enumerate().map(|(index, value_ref)| {
-                let (r,c,q) = FIELDS[index];
+                let (r,c,q) = s.fields[index];
                 let value = *value_ref;
                 if value > 0 {
                     let mov_zero_based = (value - 1) as usize;
@@ -76,20 +840,9 @@ impl Sudoku {
         }
     }
 
-    fn hint(&mut self) -> Option<(usize, usize)> {
-        let mut fields = FIELDS.clone().into_iter()
-            .filter(|(r,c,_)| self.values[(*r, *c)] == 0)
-            .collect::<Vec<(usize, usize, usize)>>();
-        fields.shuffle(&mut ThreadRng::default());
-        fields.get(self.get_weakest_clue_idx_in(&fields)).map(|clue| {
-            let (r,c,_) = *clue;
-            (r,c)
-        })
-    }
-
     fn solve_random(&mut self, cursor:usize, seed:u64) -> bool {
-        if cursor < 81 {
-            let rcq = FIELDS[cursor];
+        if cursor < self.fields.len() {
+            let rcq = self.fields[cursor];
             return match self.get_possible_moves_rnd(rcq, &mut StdRng::seed_from_u64(seed)) {
                 None => self.solve_random(cursor + 1, seed),
                 Some(moves) => {
@@ -109,9 +862,9 @@ impl Sudoku {
         true
     }
 
-    fn solve(&mut self, cursor:usize) -> bool {
-        if cursor < 81 {
-            let rcq = FIELDS[cursor];
+    pub(crate) fn solve(&mut self, cursor:usize) -> bool {
+        if cursor < self.fields.len() {
+            let rcq = self.fields[cursor];
             return match self.get_possible_moves(rcq) {
                 None => self.solve(cursor + 1),
                 Some(moves) => {
@@ -131,33 +884,158 @@ impl Sudoku {
         true
     }
 
-    fn generate(&mut self, cursor:usize, fields:&Vec<(usize, usize, usize)>, removed_count:u8, difficulty:u8) -> bool {
-        if cursor >= fields.len() || self.count_solutions(0, 2) > 1 {
+    // Removes the fields of each orbit together, as long as the solution stays unique
+    fn generate(&mut self, cursor:usize, orbits:&Vec<Vec<(usize, usize, usize)>>, removed_count:u16, difficulty:u8, solver:&dyn Solver, monitor:&mut Monitor) -> bool {
+        if !monitor.report(removed_count) {
             return false;
         }
 
-        if removed_count >= difficulty as u8 {
-            return true;
+        if cursor >= orbits.len() || solver.count_solutions(&self.variant, &self.values, 2) > 1 {
+            return false;
         }
 
-        let rcq = fields[cursor];
-        let (r,c,_) = rcq;
-        let mov = self.values[(r,c)];
-        let mov_zero_based = (mov - 1) as usize;
+        if removed_count >= difficulty as u16 {
+            return true;
+        }
 
-        self.reset_value(rcq, mov_zero_based);
+        let orbit = &orbits[cursor];
+        let moves:Vec<usize> = orbit.iter().map(|(r,c,_)| (self.values[(*r,*c)] - 1) as usize).collect();
+        for (rcq, mov_zero_based) in orbit.iter().zip(moves.iter()) {
+            self.reset_value(*rcq, *mov_zero_based);
+        }
         
-        if self.generate(cursor + 1, fields, removed_count + 1, difficulty) {
+        if self.generate(cursor + 1, orbits, removed_count + orbit.len() as u16, difficulty, solver, monitor) {
             return true;
         }
 
-        self.set_value(rcq, mov_zero_based);
-        self.generate(cursor + 1, fields, removed_count, difficulty)
+        for (rcq, mov_zero_based) in orbit.iter().zip(moves.iter()) {
+            self.set_value(*rcq, *mov_zero_based);
+        }
+        if monitor.token.is_cancelled() {
+            return false;
+        }
+        self.generate(cursor + 1, orbits, removed_count, difficulty, solver, monitor)
+    }
+
+    // Removes each orbit that can be removed without losing uniqueness, which results in a minimal grid.
+    // A clue that can't be removed stays necessary, as removing further clues only adds solutions.
+    fn reduce(&mut self, orbits:&Vec<Vec<(usize, usize, usize)>>, solver:&dyn Solver, monitor:&mut Monitor) -> bool {
+        let mut removed_count = 0;
+        for orbit in orbits {
+            if !monitor.report(removed_count) {
+                return false;
+            }
+
+            let moves:Vec<usize> = orbit.iter().map(|(r,c,_)| (self.values[(*r,*c)] - 1) as usize).collect();
+            for (rcq, mov_zero_based) in orbit.iter().zip(moves.iter()) {
+                self.reset_value(*rcq, *mov_zero_based);
+            }
+
+            if solver.count_solutions(&self.variant, &self.values, 2) > 1 {
+                for (rcq, mov_zero_based) in orbit.iter().zip(moves.iter()) {
+                    self.set_value(*rcq, *mov_zero_based);
+                }
+            } else {
+                removed_count += orbit.len() as u16;
+            }
+        }
+        true
+    }
+
+    // Fills hidden singles and the field with the fewest possible moves first.
+    // Cages and irregular regions are pruned much earlier this way than in row-major order.
+    fn solve_constrained(&mut self, rng_option:&mut Option<StdRng>) -> bool {
+        match self.most_constrained_placements() {
+            None => true,
+            Some(mut placements) => {
+                if let Some(rng) = rng_option.as_mut() {
+                    placements.shuffle(rng);
+                }
+                for (rcq, mov_zero_based) in placements {
+                    self.set_value(rcq, mov_zero_based);
+
+                    if self.solve_constrained(rng_option) {
+                        return true;
+                    }
+
+                    self.reset_value(rcq, mov_zero_based);
+                }
+                false
+            }
+        }
+    }
+
+    fn count_solutions_constrained(&mut self, limit:u8) -> u8 {
+        match self.most_constrained_placements() {
+            None => 1,
+            Some(placements) => {
+                let mut sum = 0;
+                for (rcq, mov_zero_based) in placements {
+                    self.set_value(rcq, mov_zero_based);
+
+                    sum += self.count_solutions_constrained(limit);
+
+                    self.reset_value(rcq, mov_zero_based);
+                    if sum >= limit {
+                        return limit;
+                    }
+                }
+                sum
+            }
+        }
+    }
+
+    // The placements to try next: a digit that fits in only one field of a unit, otherwise the possible moves
+    // of the empty field with the fewest of them. None are left on a dead end, where a field or a missing digit
+    // of a unit has no place. Returns none if all fields are filled.
+    fn most_constrained_placements(&self) -> Option<Vec<Placement>> {
+        let mut masks = vec![0; self.digits * self.digits];
+        let mut best:Option<((usize, usize, usize), u16)> = Option::None;
+        let mut best_count = self.digits + 1;
+        for rcq in self.fields.iter().copied() {
+            let (r,c,_) = rcq;
+            if self.values[(r,c)] > 0 {
+                continue;
+            }
+            let poss = self.get_possible_mask(rcq);
+            let count = poss.view_bits::<Lsb0>()[0..self.digits].count_ones();
+            if count < best_count {
+                best = Option::Some((rcq, poss));
+                best_count = count;
+                if count <= 1 {
+                    break;
+                }
+            }
+            masks[r*self.digits + c] = poss;
+        }
+        let best = best.map(|(rcq, poss)| poss.view_bits::<Lsb0>()[0..self.digits].iter_ones().map(|mov_zero_based| (rcq, mov_zero_based)).collect());
+        if best_count <= 1 {
+            return best;
+        }
+
+        // hidden singles prune irregular regions much earlier, the fields are still better to branch on otherwise
+        for unit in self.units.iter() {
+            let placed = unit.iter()
+                .filter(|(r,c,_)| self.values[(*r,*c)] > 0)
+                .fold(0, |placed, (r,c,_)| placed | VALUES_BIN[(self.values[(*r,*c)] - 1) as usize]);
+            for (mov_zero_based, mov_bin) in VALUES_BIN[0..self.digits].iter().enumerate() {
+                if placed & mov_bin > 0 {
+                    continue;
+                }
+                let mut places = unit.iter().filter(|(r,c,_)| masks[r*self.digits + c] & mov_bin > 0);
+                match (places.next(), places.next()) {
+                    (None, _) => return Option::Some(Vec::new()),
+                    (Some(rcq), None) => return Option::Some(vec![(*rcq, mov_zero_based)]),
+                    _ => {},
+                }
+            }
+        }
+        best
     }
 
     fn count_solutions(&mut self, cursor:usize, limit:u8) -> u8 {
-        if cursor < 81 {
-            let rcq = FIELDS[cursor];
+        if cursor < self.fields.len() {
+            let rcq = self.fields[cursor];
             return match self.get_possible_moves(rcq) {
                 None => self.count_solutions(cursor + 1, limit),
                 Some(moves) => {
@@ -179,22 +1057,20 @@ impl Sudoku {
         1
     }
 
-    fn set_value(&mut self, rcq:(usize, usize, usize), mov_zero_based:usize) {
-        let mov_bin_inv = VALUES_BIN_INV[mov_zero_based];
-        let (r, c, q) = rcq;
-        self.poss_rows[r] &= mov_bin_inv;
-        self.poss_cols[c] &= mov_bin_inv;
-        self.poss_quads[q] &= mov_bin_inv;
+    pub(crate) fn set_value(&mut self, rcq:(usize, usize, usize), mov_zero_based:usize) {
+        let (r, c, _) = rcq;
         self.values[(r,c)] = (mov_zero_based + 1) as u8;
+        for constraint in self.constraints.iter_mut() {
+            constraint.place((r,c), mov_zero_based);
+        }
     }
 
     fn reset_value(&mut self, rcq:(usize, usize, usize), mov_zero_based:usize) {
-        let mov_bin = VALUES_BIN[mov_zero_based];
-        let (r, c, q) = rcq;
+        let (r, c, _) = rcq;
         self.values[(r,c)] = 0;
-        self.poss_rows[r] |= mov_bin;
-        self.poss_cols[c] |= mov_bin;
-        self.poss_quads[q] |= mov_bin;
+        for constraint in self.constraints.iter_mut() {
+            constraint.unplace((r,c), mov_zero_based);
+        }
     }
 
     fn get_possible_moves_rnd<R>(&self, rcq:(usize, usize, usize), rng: &mut R) -> Option<Vec<usize>>
@@ -208,45 +1084,96 @@ impl Sudoku {
     }
 
     fn get_possible_moves(&self, rcq:(usize, usize, usize)) -> Option<Vec<usize>> {
-        let (r, c, q) = rcq;
+        let (r, c, _) = rcq;
         let val: u8 = self.values[(r,c)];
         if val > 0 {
             return Option::None;
         }
         
-        let poss:u16 = self.poss_rows[r] & self.poss_cols[c] & self.poss_quads[q];
-        Option::Some(poss.view_bits::<Lsb0>()[0..9].iter_ones().collect())
+        let poss:u16 = self.get_possible_mask(rcq);
+        Option::Some(poss.view_bits::<Lsb0>()[0..self.digits].iter_ones().collect())
+    }
+
+    pub(crate) fn get_possible_mask(&self, rcq:(usize, usize, usize)) -> u16 {
+        let (r, c, _) = rcq;
+        self.constraints.iter().fold(ALL_DIGITS, |poss, constraint| constraint.filter((r,c), &self.values, poss))
     }
 
-    fn get_weakest_clue_idx_in(&self, fields:&Vec<(usize, usize, usize)>) -> usize {
-        let mut weakest_strength = 10;
+    fn get_weakest_clue_idx_in(&mut self, fields:&Vec<(usize, usize, usize)>) -> usize {
+        let mut weakest_strength = self.digits + 1;
         let mut weakest_clue_idx = 0;
         for (clue_idx, rcq_ref) in fields.iter().enumerate() {
-            let (r,c,q) = *rcq_ref;
+            let (r,c,_) = *rcq_ref;
             let value = self.values[(r,c)];
 
+            // the candidates of a clue are those of its field without it
             let poss = match value == 0 {
-                true => {
-                    self.poss_rows[r] & self.poss_cols[c] & self.poss_quads[q]
-                },
+                true => self.get_possible_mask(*rcq_ref),
                 false => {
                     let mov_zero_based = (value - 1) as usize;
-                    let mov_bin = VALUES_BIN[mov_zero_based];
-                    (self.poss_rows[r] | mov_bin) & (self.poss_cols[c] | mov_bin) & (self.poss_quads[q] | mov_bin)
+                    self.reset_value(*rcq_ref, mov_zero_based);
+                    let poss = self.get_possible_mask(*rcq_ref);
+                    self.set_value(*rcq_ref, mov_zero_based);
+                    poss
                 }
             };
             
-            let strength = poss.view_bits::<Lsb0>()[0..9].count_ones();
+            let strength = poss.view_bits::<Lsb0>()[0..self.digits].count_ones();
             if strength < weakest_strength {
                 weakest_strength = strength;
                 weakest_clue_idx = clue_idx;
             }
         }
-        weakest_clue_idx
+        weakest_clue_idx
+    }
+}
+
+impl Solver for Backtracker {
+    fn solve(&self, variant:&Variant, values:&Array2D<u8>, seed_option:Option<u64>) -> Result<Array2D<u8>, String> {
+        Sudoku::with_variant(variant, Option::Some(values)).and_then(|mut s| {
+            let solved = match (s.variant.is_row_major(), seed_option) {
+                (true, Some(seed)) => s.solve_random(0, seed),
+                (true, None) => s.solve(0),
+                (false, _) => s.solve_constrained(&mut seed_option.map(StdRng::seed_from_u64)),
+            };
+            match solved {
+                true => Ok(s.values),
+                false => Err("No solution found".into()),
+            }
+        })
+    }
+
+    fn count_solutions(&self, variant:&Variant, values:&Array2D<u8>, limit:u8) -> u8 {
+        Sudoku::with_variant(variant, Option::Some(values))
+            .map(|mut s| match s.variant.is_row_major() {
+                true => s.count_solutions(0, limit),
+                false => s.count_solutions_constrained(limit),
+            })
+            .unwrap_or_else(|_| 0)
+    }
+}
+
+// Groups the fields sequence into orbits of the symmetry, ordered by their first field in the sequence.
+// Orbits reaching into preset fields can't be removed and are skipped.
+fn generate_orbits(fields_sequence: &[(usize, usize, usize)], variant:&Variant, symmetry:Symmetry) -> Vec<Vec<(usize, usize, usize)>> {
+    let digits = variant.size.digits();
+    let mut assigned = vec![false; digits * digits];
+    let mut orbits = Vec::new();
+    for (r,c,_) in fields_sequence.iter() {
+        if assigned[r*digits + c] {
+            continue;
+        }
+
+        let orbit:Vec<(usize, usize, usize)> = symmetry.orbit(*r, *c, digits).into_iter().map(|(r,c)| (r, c, variant.region(r, c))).collect();
+        orbit.iter().for_each(|(r,c,_)| assigned[r*digits + c] = true);
+        if orbit.iter().all(|rcq| fields_sequence.contains(rcq)) {
+            orbits.push(orbit);
+        }
     }
+    orbits
 }
 
-fn generate_sequence(values: Array2D<u8>, seed:u64, mut fields: Vec<(usize, usize, usize)>) -> Result<Vec<(usize, usize, usize)>, String> {
+fn generate_sequence(variant:&Variant, values: Array2D<u8>, seed:u64, mut fields: Vec<(usize, usize, usize)>) -> Result<Vec<(usize, usize, usize)>, String> {
     // Try to remove weak clues and keep few strong ones
     // The strength of an existing clue is the number of possibilities in the field when the clue is removed.
     // values must be fully filled
@@ -254,7 +1181,8 @@ fn generate_sequence(values: Array2D<u8>, seed:u64, mut fields: Vec<(usize, usiz
 
     let mut cursor_queue: Vec<(usize, usize, usize)> = Vec::new();
 
-    Sudoku::new(Option::Some(&values)).map(|mut s| {
+    let regular = Variant { cages: vec![], ..variant.clone() };
+    Sudoku::with_variant(&regular, Option::Some(&values)).map(|mut s| {
         while fields.len() > 0 {
             let weakest_clue = fields.remove(s.get_weakest_clue_idx_in(&fields));
             let (r,c,_) = weakest_clue;
@@ -266,82 +1194,414 @@ fn generate_sequence(values: Array2D<u8>, seed:u64, mut fields: Vec<(usize, usiz
     })
 }
 
-pub fn hint(values: &Array2D<u8>) -> Result<(usize, usize), String> {
-    let result = Sudoku::new(Option::Some(values));
-    if result.is_err() {
-        return Err(result.unwrap_err());
-    }
-
-    let mut sudoku = result.unwrap();
-    sudoku.hint().ok_or("No hint found".into())
+// The random solution grid generated grids are reduced from. Seeds are shared and stored with saved games and statistics,
+// so it is always drawn by the backtracker, which generated all grids before the backends could be chosen.
+fn seeded_solution(variant:&Variant, values: &Array2D<u8>, seed:u64) -> Result<Array2D<u8>, String> {
+    Backtracker.solve(variant, values, Option::Some(seed))
 }
 
-/// Solves a given Sudoku grid.
+/// Solves a given Sudoku grid under the rules of the `variant`.
 /// Given values are not changed. If `seed` is provided, solve is performed randomly. 
 /// This could lead to inconsistent results when multiple solutions are possible.
 /// For optimal performance, provide `Option::None`.
-pub fn solve(values: &Array2D<u8>, seed_option:Option<u64>) -> Result<Array2D<u8>, String> {
-    Sudoku::new(Option::Some(values)).map(|mut s| {
-        match seed_option {
-            Some(seed) => s.solve_random(0, seed),
-            None => s.solve(0),
-        };
-        s.values
-    })
+pub fn solve(variant:&Variant, values: &Array2D<u8>, seed_option:Option<u64>) -> Result<Array2D<u8>, String> {
+    solve_with(Backend::default(), variant, values, seed_option)
 }
 
-/// Generates a Sudoku grid of the desired difficulty with a unique solution.
+/// Solves a given Sudoku grid with the given backend, see [`solve`].
+pub fn solve_with(backend:Backend, variant:&Variant, values: &Array2D<u8>, seed_option:Option<u64>) -> Result<Array2D<u8>, String> {
+    backend.solver().solve(variant, values, seed_option)
+}
+
+/// Generates a Sudoku grid of the desired difficulty with a unique solution under the rules of the `variant`.
 /// The difficulty is the number of empty fields. The non-zero fields of the provided 
 /// values-grid are preserved. As a result, a tuple of `(clues, solution)` is returned.
-pub fn generate(values: &Array2D<u8>, seed:u64, difficulty:u8) -> Result<(Array2D<u8>, Array2D<u8>), String> {
-    if difficulty > 58 {
-        return Err("Maximum difficulty is 58".into());
+/// Clues are removed in orbits of the given `symmetry`, which may exceed the difficulty by a few fields.
+/// Solutions are counted with the given backend. Progress is reported to the `monitor`, which can also cancel the generation.
+pub fn generate_monitored(backend:Backend, variant:&Variant, values: &Array2D<u8>, seed:u64, difficulty:u8, symmetry:Symmetry, monitor:&mut Monitor) -> Result<(Array2D<u8>, Array2D<u8>), String> {
+    let solution = seeded_solution(variant, values, seed)?;
+    remove_clues(backend, variant, values, solution, Removal { seed, difficulty: Option::Some(difficulty), symmetry }, monitor)
+}
+
+/// Generates a minimal Sudoku grid with the default backend, see [`generate_minimal_monitored`].
+#[cfg(test)]
+pub fn generate_minimal(variant:&Variant, values: &Array2D<u8>, seed:u64, symmetry:Symmetry) -> Result<(Array2D<u8>, Array2D<u8>), String> {
+    generate_minimal_monitored(Backend::default(), variant, values, seed, symmetry, &mut Monitor::unobserved())
+}
+
+/// Generates a minimal Sudoku grid with a unique solution, where no clue can be removed without losing uniqueness.
+/// The non-zero fields of the provided values-grid are preserved and therefore not considered for removal.
+/// With a `symmetry` other than [`Symmetry::None`], clues are only removed in whole orbits.
+/// As a result, a tuple of `(clues, solution)` is returned.
+/// Solutions are counted with the given backend. Progress is reported to the `monitor`, which can also cancel the generation.
+pub fn generate_minimal_monitored(backend:Backend, variant:&Variant, values: &Array2D<u8>, seed:u64, symmetry:Symmetry, monitor:&mut Monitor) -> Result<(Array2D<u8>, Array2D<u8>), String> {
+    let solution = seeded_solution(variant, values, seed)?;
+    remove_clues(backend, variant, values, solution, Removal { seed, difficulty: Option::None, symmetry }, monitor)
+}
+
+/// Generates a Killer Sudoku with the default backend, see [`generate_killer_monitored`].
+#[cfg(test)]
+pub fn generate_killer(variant:&Variant, values: &Array2D<u8>, seed:u64, difficulty:Option<u8>, symmetry:Symmetry) -> Result<(Variant, Array2D<u8>, Array2D<u8>), String> {
+    generate_killer_monitored(Backend::default(), variant, values, seed, difficulty, symmetry, &mut Monitor::unobserved())
+}
+
+/// Generates a Killer Sudoku with a random cage layout and a unique solution.
+/// The cages replace those of the `variant`, all other rules are kept.
+/// Clues are removed like in [`generate_monitored`], or until the grid is minimal if no `difficulty` is provided.
+/// As a result, a tuple of `(variant, clues, solution)` is returned.
+/// Solutions are counted with the given backend. Progress is reported to the `monitor`, which can also cancel the generation.
+pub fn generate_killer_monitored(backend:Backend, variant:&Variant, values: &Array2D<u8>, seed:u64, difficulty:Option<u8>, symmetry:Symmetry, monitor:&mut Monitor) -> Result<(Variant, Array2D<u8>, Array2D<u8>), String> {
+    let mut killer = Variant { cages: vec![], ..variant.clone() };
+    let solution = seeded_solution(&killer, values, seed)?;
+    killer.cages = generate_cages(&solution, seed);
+    let (clues, solution) = remove_clues(backend, &killer, values, solution, Removal { seed, difficulty, symmetry }, monitor)?;
+    Ok((killer, clues, solution))
+}
+
+/// Partitions the grid into random cages of orthogonally connected fields.
+/// The digits of the `solution` within a cage are distinct and make up its sum.
+pub fn generate_cages(solution: &Array2D<u8>, seed:u64) -> Vec<Cage> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let digits = solution.num_rows();
+    let mut cage_of:Vec<Option<usize>> = vec![Option::None; digits * digits];
+    let mut cages:Vec<Vec<(usize, usize)>> = Vec::new();
+
+    let mut fields:Vec<(usize, usize)> = (0..digits * digits).map(|idx| (idx / digits, idx % digits)).collect();
+    fields.shuffle(&mut rng);
+    for (r,c) in fields {
+        if cage_of[r*digits + c].is_some() {
+            continue;
+        }
+
+        let cage_idx = cages.len();
+        let size = rng.gen_range(MIN_CAGE_SIZE..=MAX_CAGE_SIZE);
+        let mut cells = vec![(r,c)];
+        cage_of[r*digits + c] = Option::Some(cage_idx);
+        while cells.len() < size {
+            let candidates:Vec<(usize, usize)> = cells.iter()
+                .flat_map(|(r,c)| neighbours(*r, *c, digits))
+                .filter(|(r,c)| cage_of[r*digits + c].is_none())
+                .filter(|rc| cells.iter().all(|cell| solution[*cell] != solution[*rc]))
+                .collect();
+            match candidates.choose(&mut rng) {
+                Some((r,c)) => {
+                    cage_of[r*digits + c] = Option::Some(cage_idx);
+                    cells.push((*r,*c));
+                },
+                None => break,
+            }
+        }
+        cages.push(cells);
     }
-    
-    let nullable_fields: Vec<(usize, usize, usize)> = FIELDS.into_iter().filter(|rcq_ref| {
+
+    // single fields would simply reveal their digit, so they are merged into a neighbouring cage if possible
+    for cage_idx in 0..cages.len() {
+        if cages[cage_idx].len() != 1 {
+            continue;
+        }
+        let (r,c) = cages[cage_idx][0];
+        let target = neighbours(r, c, digits).into_iter()
+            .filter_map(|(r,c)| cage_of[r*digits + c])
+            .find(|idx| cages[*idx].len() > 1 && cages[*idx].iter().all(|cell| solution[*cell] != solution[(r,c)]));
+        if let Some(target_idx) = target {
+            cages[cage_idx].clear();
+            cages[target_idx].push((r,c));
+            cage_of[r*digits + c] = Option::Some(target_idx);
+        }
+    }
+
+    cages.into_iter()
+        .filter(|cells| !cells.is_empty())
+        .map(|cells| Cage {
+            sum: cells.iter().map(|cell| solution[*cell]).sum(),
+            cells,
+        })
+        .collect()
+}
+
+/// Generates a random Jigsaw layout without monitoring, see [`generate_regions_monitored`].
+#[cfg(test)]
+pub fn generate_regions(size:Size, seed:u64) -> Result<Vec<Vec<usize>>, String> {
+    generate_regions_monitored(size, seed, &mut Monitor::unobserved())
+}
+
+/// Generates a random Jigsaw layout of contiguous regions with as many fields as the grid has digits.
+/// Starting from the quads, fields are swapped between neighbouring regions. Layouts without a solution are discarded.
+/// As a result, the region index of every field is returned row by row.
+/// Fails if none of the first layouts has a solution.
+/// Every layout is reported to the `monitor`, which can also cancel the generation.
+pub fn generate_regions_monitored(size:Size, seed:u64, monitor:&mut Monitor) -> Result<Vec<Vec<usize>>, String> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let digits = size.digits();
+    for _ in 0..MAX_REGION_ATTEMPTS {
+        if !monitor.report(0) {
+            return Err("Generation cancelled".into());
+        }
+
+        let mut regions:Vec<Vec<usize>> = (0..digits).map(|r| (0..digits).map(|c| size.quad(r, c)).collect()).collect();
+        for _ in 0..digits * digits * REGION_SWAPS_PER_FIELD {
+            let (r,c) = (rng.gen_range(0..digits), rng.gen_range(0..digits));
+            let from = regions[r][c];
+            let targets:Vec<(usize, usize)> = neighbours(r, c, digits).into_iter().filter(|(r,c)| regions[*r][*c] != from).collect();
+            let to = match targets.choose(&mut rng) {
+                Some((r,c)) => regions[*r][*c],
+                None => continue,
+            };
+
+            // the field joins the neighbouring region, which hands back one of its fields bordering the remainder
+            regions[r][c] = to;
+            let returns:Vec<(usize, usize)> = (0..digits * digits)
+                .map(|idx| (idx / digits, idx % digits))
+                .filter(|rc| *rc != (r,c) && regions[rc.0][rc.1] == to)
+                .filter(|(r,c)| neighbours(*r, *c, digits).iter().any(|(r,c)| regions[*r][*c] == from))
+                .collect();
+            match returns.choose(&mut rng) {
+                Some((r_back,c_back)) => {
+                    regions[*r_back][*c_back] = from;
+                    if !is_contiguous(&regions, from) || !is_contiguous(&regions, to) {
+                        regions[*r_back][*c_back] = to;
+                        regions[r][c] = from;
+                    }
+                },
+                None => regions[r][c] = from,
+            }
+        }
+
+        let variant = Variant { size, regions: Option::Some(regions.clone()), ..Variant::default() };
+        if solve(&variant, &Array2D::filled_with(0, digits, digits), Option::Some(rng.gen())).is_ok() {
+            return Ok(regions);
+        }
+    }
+    Err("No solvable region layout found".into())
+}
+
+// orthogonally adjacent fields in a grid with `digits` rows and cols
+fn neighbours(r:usize, c:usize, digits:usize) -> Vec<(usize, usize)> {
+    let mut neighbours = Vec::new();
+    if r > 0 { neighbours.push((r - 1, c)); }
+    if r + 1 < digits { neighbours.push((r + 1, c)); }
+    if c > 0 { neighbours.push((r, c - 1)); }
+    if c + 1 < digits { neighbours.push((r, c + 1)); }
+    neighbours
+}
+
+// How clues are removed from a solution grid, see `remove_clues`
+struct Removal {
+    seed: u64,
+    // number of empty fields to reach, until the grid is minimal without one
+    difficulty: Option<u8>,
+    symmetry: Symmetry,
+}
+
+// Removes clues of the solution until the difficulty is reached, or until the grid is minimal without a difficulty
+fn remove_clues(backend:Backend, variant:&Variant, values: &Array2D<u8>, solution:Array2D<u8>, removal:Removal, monitor:&mut Monitor) -> Result<(Array2D<u8>, Array2D<u8>), String> {
+    let Removal { seed, difficulty, symmetry } = removal;
+    if values.num_rows() != variant.size.digits() || values.num_columns() != variant.size.digits() {
+        return Err("Grid size doesn't match the variant".into());
+    }
+    let nullable_fields: Vec<(usize, usize, usize)> = variant.fields().into_iter().filter(|rcq_ref| {
         let (r,c,_) = *rcq_ref;
         values[(r,c)] == 0
     }).collect();
-    if nullable_fields.len() < difficulty as usize {
-        return Err("difficulty must be less than or equal to the number of empty fields".into());
+
+    if let Some(difficulty) = difficulty {
+        // classic 9×9 grids are limited to 58 empty fields and smaller grids in proportion.
+        // Larger grids get much harder to reduce and are limited to 5/8 of their fields. Cages may replace all clues.
+        let field_count = variant.size.digits() * variant.size.digits();
+        let max_difficulty = match (variant.cages.is_empty(), variant.size.digits() > 9) {
+            (true, false) => field_count * 58 / 81,
+            (true, true) => field_count * 5 / 8,
+            (false, _) => field_count.min(u8::MAX as usize),
+        };
+        if difficulty as usize > max_difficulty {
+            return Err(format!("Maximum difficulty is {}", max_difficulty));
+        }
+        if nullable_fields.len() < difficulty as usize {
+            return Err("difficulty must be less than or equal to the number of empty fields".into());
+        }
     }
 
-    let solve_result = solve(values, Option::Some(seed));
-    if solve_result.is_err() {
-        return Err(solve_result.unwrap_err());
+    let fields_sequence = generate_sequence(variant, solution.clone(), seed, nullable_fields)?;
+    let orbits = generate_orbits(&fields_sequence, variant, symmetry);
+    let mut sudoku = Sudoku::with_variant(variant, Option::Some(&solution))?;
+    let generated = match difficulty {
+        Some(difficulty) => sudoku.generate(0, &orbits, 0, difficulty, backend.solver(), monitor),
+        None => sudoku.reduce(&orbits, backend.solver(), monitor),
+    };
+    if !generated {
+        if monitor.token.is_cancelled() {
+            return Err("Generation cancelled".into());
+        }
+        return Err("Error occured during solution generation".into());
     }
 
-    let solution = solve_result.unwrap();
-    let fields_sequence_result = generate_sequence(solution.clone(), seed, nullable_fields);
-    if fields_sequence_result.is_err() {
-        return Err(fields_sequence_result.unwrap_err());
+    Ok((sudoku.values, solution))
+}
+
+/// Returns the clues `(row, col)` that can be removed individually without losing uniqueness.
+/// Fails if the grid doesn't have a unique solution under the rules of the `variant`.
+pub fn redundant_clues(variant:&Variant, values: &Array2D<u8>) -> Result<Vec<(usize, usize)>, String> {
+    if count_solutions(variant, values, 2) != 1 {
+        return Err("Grid must have a unique solution".into());
     }
 
-    let fields_sequence = &fields_sequence_result.unwrap();
-    let result = Sudoku::new(Option::Some(&solution));
-    if result.is_err() {
-        return Err(result.unwrap_err());
+    let mut reduced = values.clone();
+    Ok(variant.fields().into_iter()
+        .map(|(r,c,_)| (r,c))
+        .filter(|rc| values[*rc] > 0)
+        .filter(|rc| {
+            reduced[*rc] = 0;
+            let redundant = count_solutions(variant, &reduced, 2) == 1;
+            reduced[*rc] = values[*rc];
+            redundant
+        })
+        .collect())
+}
+
+/// Returns the candidates of every empty field as a bitmask, where bit `d - 1` stands for digit `d`.
+/// Filled fields have no candidates. Fails if the values conflict under the rules of the `variant`.
+pub fn candidates(variant:&Variant, values: &Array2D<u8>) -> Result<Array2D<u16>, String> {
+    let sudoku = Sudoku::with_variant(variant, Option::Some(values))
+        .map_err(|_| "Candidates can't be computed for a grid with conflicts".to_string())?;
+    let digits = variant.size.digits();
+    let mut candidates = Array2D::filled_with(0, digits, digits);
+    for rcq in sudoku.fields.iter().copied() {
+        let (r,c,_) = rcq;
+        if sudoku.values[(r,c)] == 0 {
+            candidates[(r,c)] = sudoku.get_possible_mask(rcq) & (ALL_DIGITS >> (MAX_DIGITS - digits));
+        }
     }
-    
-    let mut sudoku = result.unwrap();
-    if !sudoku.generate(0, fields_sequence, 0, difficulty){
-        return Err("Error occured during solution generation".into());
+    Ok(candidates)
+}
+
+/// Checks whether the grid has a unique solution and none of its clues can be removed.
+#[cfg(test)]
+pub fn is_minimal(variant:&Variant, values: &Array2D<u8>) -> bool {
+    redundant_clues(variant, values).is_ok_and(|redundant| redundant.is_empty())
+}
+
+/// Generates a Sudoku grid of the desired grade without monitoring, see [`generate_rated_monitored`].
+#[cfg(test)]
+pub fn generate_rated(values: &Array2D<u8>, seed:u64, grade:Grade, symmetry:Symmetry) -> Result<(Array2D<u8>, Array2D<u8>, Rating), String> {
+    generate_rated_monitored(values, seed, grade, symmetry, &mut Monitor::unobserved())
+}
+
+/// Generates a Sudoku grid with a unique solution that is rated with the desired grade.
+/// Clues are removed from one solution grid and the grid is re-rated after every removal,
+/// removals that make the grid harder than desired are restored. Unlike [`generate_monitored`], the number
+/// of removed clues is not capped, so the grid may be reduced down to a minimal one.
+/// Easy and Medium grids keep removing clues up to 44 and 50 empty fields, harder grades are returned
+/// as soon as they are reached. If a solution grid can't be reduced to the grade, the next one is tried.
+/// As a result, a tuple of `(clues, solution, rating)` is returned.
+/// Progress of all attempts is reported to the `monitor`, which can also cancel the generation.
+pub fn generate_rated_monitored(values: &Array2D<u8>, seed:u64, grade:Grade, symmetry:Symmetry, monitor:&mut Monitor) -> Result<(Array2D<u8>, Array2D<u8>, Rating), String> {
+    let variant = Variant::default();
+    if values.num_rows() != variant.size.digits() || values.num_columns() != variant.size.digits() {
+        return Err("Grid size doesn't match the variant".into());
     }
+    let min_removed = match grade {
+        Grade::Easy => 44,
+        Grade::Medium => 50,
+        Grade::Hard | Grade::Extreme => 0,
+    };
+    let nullable_fields: Vec<(usize, usize, usize)> = variant.fields().into_iter().filter(|rcq_ref| {
+        let (r,c,_) = *rcq_ref;
+        values[(r,c)] == 0
+    }).collect();
 
-    Ok((sudoku.values, solution))
+    for attempt in 0..MAX_RATED_ATTEMPTS {
+        let attempt_seed = seed.wrapping_add(attempt);
+        let solution = seeded_solution(&variant, values, attempt_seed)?;
+        let fields_sequence = generate_sequence(&variant, solution.clone(), attempt_seed, nullable_fields.clone())?;
+        let orbits = generate_orbits(&fields_sequence, &variant, symmetry);
+
+        let mut clues = solution.clone();
+        let mut rating = logic::rate(&clues)?;
+        let mut removed:u16 = 0;
+        for orbit in orbits.iter() {
+            if rating.grade == grade && removed >= min_removed {
+                break;
+            }
+            if !monitor.report(removed) {
+                return Err("Generation cancelled".into());
+            }
+
+            orbit.iter().for_each(|(r,c,_)| clues[(*r,*c)] = 0);
+            let candidate_rating = match count_solutions(&variant, &clues, 2) {
+                1 => Option::Some(logic::rate(&clues)?),
+                _ => Option::None,
+            };
+            match candidate_rating {
+                Some(candidate_rating) if candidate_rating.grade <= grade => {
+                    rating = candidate_rating;
+                    removed += orbit.len() as u16;
+                },
+                _ => orbit.iter().for_each(|(r,c,_)| clues[(*r,*c)] = solution[(*r,*c)]),
+            }
+        }
+
+        if rating.grade == grade {
+            return Ok((clues, solution, rating));
+        }
+    }
+    Err("No grid found for the desired grade".into())
+}
+
+/// Parses a grid in the common 81-character format, row by row, with `.` or `0` for blanks.
+/// Whitespace is ignored. The grid is validated for conflicts.
+pub fn parse_grid(string:&str) -> Result<Array2D<u8>, String> {
+    let values = string.chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| match c {
+            '.' | '0' => Ok(0),
+            '1'..='9' => Ok(c as u8 - b'0'),
+            _ => Err(format!("Invalid character '{}'", c)),
+        })
+        .collect::<Result<Vec<u8>, String>>()?;
+    if values.len() != 81 {
+        return Err("A grid must consist of exactly 81 fields".into());
+    }
+
+    let grid = Array2D::from_row_major(&values, 9, 9).map_err(|_| "Invalid grid".to_string())?;
+    Sudoku::new(Option::Some(&grid)).map(|_| grid)
+}
+
+/// Formats a grid row by row, with `.` for blanks, so classic grids come out in the common 81-character format.
+/// The values 10 to 16 of larger grids are written as `A` to `G`.
+pub fn format_grid(values:&Array2D<u8>) -> String {
+    values.elements_row_major_iter().map(|value| match value {
+        0 => '.',
+        1..=9 => (b'0' + value) as char,
+        _ => (b'A' + value - 10) as char,
+    }).collect()
 }
 
-/// Counts solutions up to a maximum of `limit`.
-pub fn count_solutions(values: &Array2D<u8>, limit:u8) -> u8 {
-    Sudoku::new(Option::Some(values))
-        .map(|mut s| s.count_solutions(0, limit))
-        .unwrap_or_else(|_| 0)
+/// Counts solutions under the rules of the `variant` up to a maximum of `limit`.
+pub fn count_solutions(variant:&Variant, values: &Array2D<u8>, limit:u8) -> u8 {
+    count_solutions_with(Backend::default(), variant, values, limit)
+}
+
+/// Counts solutions with the given backend, see [`count_solutions`].
+pub fn count_solutions_with(backend:Backend, variant:&Variant, values: &Array2D<u8>, limit:u8) -> u8 {
+    backend.solver().count_solutions(variant, values, limit)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    const HARD_PUZZLES:[&str; 9] = [
+        "100007090030020008009600500005300900010080002600004000300000010040000007007000300", // AI Escargot
+        "800000000003600000070090200050007000000045700000100030001000068008500010090000400", // Arto Inkala
+        "100000002090400050006000700050903000000070000000850040700000600030009080002000001", // Easter Monster
+        "000000039000001005003050800008090006070002000100400000009080050020000600400700000", // Golden Nugget
+        "000000012000000003002300400001800005060070800000009000008500000900040500470006000", // Platinum Blonde
+        "000000010400000000020000000000050407008000300001090000300400200050100000000806000", // 17 clues
+        "400000805030000000000700000020000060000080400000010000000603070500200000104000000", // Norvig's hardest
+        "600008940900006100070040000200610000000000200089002000000060005000000030800001600",
+        "000000000000003085001020000000507000004000100090000000500000073002010000000040009",
+    ];
+
     #[test]
     fn test_count_solutions() {
         let rows = vec![
@@ -362,7 +1622,7 @@ mod tests {
 
         use std::time::Instant;
         let now = Instant::now();
-        assert_eq!(count_solutions(&values, 100), 100);
+        assert_eq!(count_solutions(&Variant::default(), &values, 100), 100);
 
         let elapsed = now.elapsed();
         println!("Elapsed: {:.2?}", elapsed);
@@ -442,7 +1702,7 @@ mod tests {
         use std::time::Instant;
         let mut now = Instant::now();
         
-        let mut solution = solve(&values, Option::Some(5));
+        let mut solution = solve(&Variant::default(), &values, Option::Some(5));
 
         let mut elapsed = now.elapsed();
         println!("Elapsed: {:.2?}", elapsed);
@@ -452,7 +1712,7 @@ mod tests {
 
         now = Instant::now();
         
-        solution = solve(&values, Option::None);
+        solution = solve(&Variant::default(), &values, Option::None);
         
         elapsed = now.elapsed();
         println!("Elapsed: {:.2?}", elapsed);
@@ -468,7 +1728,7 @@ mod tests {
         use std::time::Instant;
         let now = Instant::now();
         
-        let solution = generate(&values, 42, 58);
+        let solution = generate_monitored(Backend::default(), &Variant::default(), &values, 42, 58, Symmetry::None, &mut Monitor::unobserved());
 
         let elapsed = now.elapsed();
         println!("Elapsed: {:.2?}", elapsed);
@@ -496,7 +1756,7 @@ mod tests {
         use std::time::Instant;
         let now = Instant::now();
         
-        let solution = generate(&values, 37, 50);
+        let solution = generate_monitored(Backend::default(), &Variant::default(), &values, 37, 50, Symmetry::None, &mut Monitor::unobserved());
 
         let elapsed = now.elapsed();
         println!("Elapsed: {:.2?}", elapsed);
@@ -513,4 +1773,452 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_generate_rated() {
+        let values = Array2D::filled_with(0, 9, 9);
+
+        use std::time::Instant;
+        let now = Instant::now();
+
+        let result = generate_rated(&values, 42, Grade::Medium, Symmetry::None);
+
+        let elapsed = now.elapsed();
+        println!("Elapsed: {:.2?}", elapsed);
+
+        assert!(result.is_ok());
+
+        let (clues, _, rating) = result.unwrap();
+        assert_eq!(rating.grade, Grade::Medium);
+        assert_eq!(logic::rate(&clues).unwrap(), rating);
+    }
+
+    #[test]
+    fn test_generate_rated_grades() {
+        let values = Array2D::filled_with(0, 9, 9);
+        for grade in [Grade::Easy, Grade::Hard, Grade::Extreme] {
+            let (clues, solution, rating) = generate_rated(&values, 42, grade, Symmetry::None).unwrap();
+            assert_eq!(rating.grade, grade);
+            assert_eq!(logic::rate(&clues).unwrap(), rating);
+            assert_eq!(count_solutions(&Variant::default(), &clues, 2), 1);
+            assert_eq!(solve(&Variant::default(), &clues, Option::None), Ok(solution));
+        }
+
+        let mut monitor = Monitor::new(CancellationToken::default(), |_| {});
+        monitor.token.cancel();
+        assert_eq!(generate_rated_monitored(&values, 42, Grade::Hard, Symmetry::None, &mut monitor), Err("Generation cancelled".into()));
+    }
+
+    #[test]
+    fn test_seeded_generation() {
+        // seeds are stored with saved games and statistics, the grids they generate must not change
+        let values = Array2D::filled_with(0, 9, 9);
+        for backend in [Backend::Backtracking, Backend::DancingLinks] {
+            let (clues, solution) = generate_monitored(backend, &Variant::default(), &values, 42, 50, Symmetry::None, &mut Monitor::unobserved()).unwrap();
+            assert_eq!(format_grid(&clues), "6.9...5..1..345.....3...721.1.....35..6.5..12....12.9.8...7.....6...915.35.4.8...");
+            assert_eq!(format_grid(&solution), "689127543127345986543986721218794635976853412435612897892571364764239158351468279");
+        }
+        assert_eq!(generate_monitored(Backend::default(), &Variant::default(), &values, 42, 50, Symmetry::None, &mut Monitor::unobserved()), generate_monitored(Backend::Backtracking, &Variant::default(), &values, 42, 50, Symmetry::None, &mut Monitor::unobserved()));
+    }
+
+    #[test]
+    fn test_backends_agree() {
+        for puzzle in HARD_PUZZLES.iter().take(5) {
+            let values = parse_grid(puzzle).unwrap();
+            assert_eq!(
+                solve_with(Backend::Backtracking, &Variant::default(), &values, Option::None),
+                solve_with(Backend::DancingLinks, &Variant::default(), &values, Option::None),
+            );
+            assert_eq!(count_solutions_with(Backend::DancingLinks, &Variant::default(), &values, 2), 1);
+        }
+
+        let mut values = parse_grid(HARD_PUZZLES[0]).unwrap();
+        values[(0,0)] = 0;
+        assert_eq!(
+            count_solutions_with(Backend::Backtracking, &Variant::default(), &values, 10),
+            count_solutions_with(Backend::DancingLinks, &Variant::default(), &values, 10),
+        );
+    }
+
+    #[test]
+    fn test_generate_with_backends() {
+        let values = Array2D::filled_with(0, 9, 9);
+        for backend in [Backend::Backtracking, Backend::DancingLinks] {
+            let (clues, solution) = generate_monitored(backend, &Variant::default(), &values, 42, 50, Symmetry::None, &mut Monitor::unobserved()).unwrap();
+            assert_eq!(count_solutions_with(Backend::Backtracking, &Variant::default(), &clues, 2), 1);
+            assert_eq!(solve_with(backend, &Variant::default(), &clues, Option::None).unwrap(), solution);
+        }
+    }
+
+    #[test]
+    #[ignore] // benchmark, run with `cargo test --release -- --ignored --nocapture`
+    fn bench_backends() {
+        use std::time::{Duration, Instant};
+
+        for backend in [Backend::Backtracking, Backend::DancingLinks] {
+            let mut total = Duration::ZERO;
+            for puzzle in HARD_PUZZLES {
+                let values = parse_grid(puzzle).unwrap();
+                let now = Instant::now();
+
+                assert!(solve_with(backend, &Variant::default(), &values, Option::None).is_ok());
+                assert_eq!(count_solutions_with(backend, &Variant::default(), &values, 2), 1);
+
+                let elapsed = now.elapsed();
+                total += elapsed;
+                println!("{:?} {}: {:.2?}", backend, puzzle, elapsed);
+            }
+            println!("{:?} total: {:.2?}", backend, total);
+        }
+    }
+
+    #[test]
+    fn test_parse_and_format_grid() {
+        let values = parse_grid(HARD_PUZZLES[0]).unwrap();
+        assert_eq!(values[(0,0)], 1);
+        assert_eq!(values[(0,1)], 0);
+        assert_eq!(values[(8,6)], 3);
+
+        let formatted = format_grid(&values);
+        assert_eq!(formatted, HARD_PUZZLES[0].replace("0", "."));
+        assert_eq!(parse_grid(&formatted).unwrap(), values);
+
+        // whitespace is ignored
+        let multiline = formatted.as_bytes().chunks(9)
+            .map(|row| String::from_utf8(row.to_vec()).unwrap())
+            .collect::<Vec<String>>()
+            .join("\n");
+        assert_eq!(parse_grid(&multiline).unwrap(), values);
+
+        let mut large = Array2D::filled_with(0, 16, 16);
+        (0..16).for_each(|col| large[(0, col)] = col as u8 + 1);
+        assert!(format_grid(&large).starts_with("123456789ABCDEFG...."));
+
+        assert!(parse_grid(&formatted[1..]).is_err());
+        assert!(parse_grid(&formatted.replacen(".", "x", 1)).is_err());
+        // conflict in row 0
+        assert!(parse_grid(&formatted.replacen(".", "1", 1)).is_err());
+    }
+
+    #[test]
+    fn test_generate_monitored() {
+        let values = Array2D::filled_with(0, 9, 9);
+
+        let mut reports:Vec<Progress> = Vec::new();
+        let mut monitor = Monitor::new(CancellationToken::default(), |progress| reports.push(progress));
+        assert!(generate_monitored(Backend::default(), &Variant::default(), &values, 42, 50, Symmetry::None, &mut monitor).is_ok());
+        drop(monitor);
+        assert!(reports.len() > 50);
+        assert!(reports.windows(2).all(|w| w[1].attempts == w[0].attempts + 1));
+        assert_eq!(reports.last().unwrap().removed_count, 50);
+
+        let token = CancellationToken::default();
+        let cancel_token = token.clone();
+        let mut monitor = Monitor::new(token, |progress| {
+            if progress.attempts >= 10 {
+                cancel_token.cancel();
+            }
+        });
+        assert_eq!(generate_monitored(Backend::default(), &Variant::default(), &values, 42, 58, Symmetry::None, &mut monitor), Err("Generation cancelled".into()));
+        assert_eq!(monitor.attempts, 10);
+    }
+
+    #[test]
+    fn test_generate_symmetric() {
+        let values = Array2D::filled_with(0, 9, 9);
+        let symmetries = [
+            Symmetry::Rotational180, Symmetry::Rotational90,
+            Symmetry::MirrorHorizontal, Symmetry::MirrorVertical,
+            Symmetry::Diagonal, Symmetry::AntiDiagonal,
+        ];
+        for symmetry in symmetries {
+            use std::time::Instant;
+            let now = Instant::now();
+
+            let (clues, _) = generate_monitored(Backend::default(), &Variant::default(), &values, 42, 46, symmetry, &mut Monitor::unobserved()).unwrap();
+
+            let elapsed = now.elapsed();
+            println!("{:?} elapsed: {:.2?}", symmetry, elapsed);
+
+            assert_eq!(count_solutions(&Variant::default(), &clues, 2), 1);
+            assert!(clues.elements_row_major_iter().filter(|value| **value == 0).count() >= 46);
+            for (r,c,_) in FIELDS {
+                let is_clue = clues[(r,c)] > 0;
+                assert!(symmetry.orbit(r, c, 9).into_iter().all(|(r2,c2)| (clues[(r2,c2)] > 0) == is_clue));
+            }
+        }
+    }
+
+    #[test]
+    fn test_generate_minimal() {
+        let values = Array2D::filled_with(0, 9, 9);
+        for symmetry in [Symmetry::None, Symmetry::Rotational180] {
+            use std::time::Instant;
+            let now = Instant::now();
+
+            let (clues, solution) = generate_minimal(&Variant::default(), &values, 42, symmetry).unwrap();
+
+            let elapsed = now.elapsed();
+            println!("{:?} elapsed: {:.2?}", symmetry, elapsed);
+
+            assert_eq!(solve(&Variant::default(), &clues, Option::None).unwrap(), solution);
+            assert_eq!(count_solutions(&Variant::default(), &clues, 2), 1);
+            if symmetry == Symmetry::None {
+                assert!(is_minimal(&Variant::default(), &clues));
+            }
+        }
+    }
+
+    #[test]
+    fn test_redundant_clues() {
+        let (clues, solution) = generate_minimal(&Variant::default(), &Array2D::filled_with(0, 9, 9), 37, Symmetry::None).unwrap();
+        assert_eq!(redundant_clues(&Variant::default(), &clues), Ok(vec![]));
+
+        // every added clue of the solution is redundant
+        let mut values = clues.clone();
+        let (r,c,_) = FIELDS.into_iter().find(|(r,c,_)| clues[(*r,*c)] == 0).unwrap();
+        values[(r,c)] = solution[(r,c)];
+        assert!(!is_minimal(&Variant::default(), &values));
+        assert!(redundant_clues(&Variant::default(), &values).unwrap().contains(&(r,c)));
+
+        assert!(redundant_clues(&Variant::default(), &Array2D::filled_with(0, 9, 9)).is_err());
+        assert!(!is_minimal(&Variant::default(), &Array2D::filled_with(0, 9, 9)));
+    }
+
+    #[test]
+    fn test_generate_cages() {
+        let solution = solve(&Variant::default(), &Array2D::filled_with(0, 9, 9), Option::Some(42)).unwrap();
+        let cages = generate_cages(&solution, 42);
+        let variant = Variant::killer(cages.clone());
+        assert!(variant.validate().is_ok());
+        assert_eq!(cages.iter().map(|cage| cage.cells.len()).sum::<usize>(), 81);
+        for cage in cages.iter() {
+            assert!(cage.cells.len() <= MAX_CAGE_SIZE + 1);
+            assert_eq!(cage.sum, cage.cells.iter().map(|cell| solution[*cell]).sum::<u8>());
+        }
+        assert!(Sudoku::with_variant(&variant, Option::Some(&solution)).is_ok());
+    }
+
+    #[test]
+    fn test_killer_constraints() {
+        let cages = vec![
+            Cage { cells: vec![(0,0), (0,1)], sum: 3 },
+            Cage { cells: vec![(1,0), (1,1), (1,2)], sum: 24 },
+        ];
+        let variant = Variant::killer(cages);
+        let mut values = Array2D::filled_with(0, 9, 9);
+        let solution = solve(&variant, &values, Option::None).unwrap();
+        assert_eq!(solution[(0,0)] + solution[(0,1)], 3);
+        assert_eq!(solution[(1,0)] + solution[(1,1)] + solution[(1,2)], 24);
+
+        // a 3 can't be completed to the sum of 3
+        values[(0,0)] = 3;
+        assert!(solve(&variant, &values, Option::None).is_err());
+        assert_eq!(count_solutions(&variant, &values, 2), 0);
+
+        assert!(Variant::killer(vec![Cage { cells: vec![(0,0), (0,1)], sum: 18 }]).validate().is_err());
+        assert!(Variant::killer(vec![
+            Cage { cells: vec![(0,0), (0,1)], sum: 5 },
+            Cage { cells: vec![(0,1), (0,2)], sum: 5 },
+        ]).validate().is_err());
+    }
+
+    #[test]
+    fn test_constraints() {
+        assert_eq!(Variant::default().constraints().len(), 3);
+        let variant = Variant {
+            cages: vec![Cage { cells: vec![(0,0), (0,1)], sum: 4 }],
+            diagonals: true,
+            anti_king: true,
+            non_consecutive: true,
+            ..Variant::default()
+        };
+        let mut constraints = variant.constraints();
+        assert_eq!(constraints.len(), 7);
+
+        let mut values = Array2D::filled_with(0, 9, 9);
+        values[(0,0)] = 1;
+        values[(2,2)] = 1;
+        values[(0,1)] = 2;
+        let conflicting:Vec<usize> = (0..constraints.len()).filter(|idx| constraints[*idx].conflicts((0,0), &values)).collect();
+        // the quad, the diagonal, the non-consecutive rule and the cage sum
+        assert_eq!(conflicting, vec![2, 3, 5, 6]);
+
+        // placed digits are removed from the candidates of the units
+        constraints.iter_mut().for_each(|constraint| constraint.place((0,0), 0));
+        let poss = constraints.iter().fold(ALL_DIGITS, |poss, constraint| constraint.filter((4,4), &values, poss));
+        assert_eq!(poss & VALUES_BIN[0], 0);
+        constraints.iter_mut().for_each(|constraint| constraint.unplace((0,0), 0));
+        values[(0,0)] = 0;
+        let poss = constraints.iter().fold(ALL_DIGITS, |poss, constraint| constraint.filter((4,4), &values, poss));
+        assert_eq!(poss & VALUES_BIN[0], VALUES_BIN[0]);
+    }
+
+    #[test]
+    fn test_generate_killer() {
+        let values = Array2D::filled_with(0, 9, 9);
+        for difficulty in [Option::Some(70), Option::None] {
+            use std::time::Instant;
+            let now = Instant::now();
+
+            let (variant, clues, solution) = generate_killer(&Variant::default(), &values, 42, difficulty, Symmetry::None).unwrap();
+
+            let elapsed = now.elapsed();
+            println!("Elapsed: {:.2?}", elapsed);
+
+            let removed = clues.elements_row_major_iter().filter(|value| **value == 0).count();
+            println!("{} cages, {} clues removed", variant.cages.len(), removed);
+            assert!(removed >= 70);
+            assert_eq!(count_solutions(&variant, &clues, 2), 1);
+            assert_eq!(solve(&variant, &clues, Option::None).unwrap(), solution);
+            assert_eq!(count_solutions_with(Backend::Backtracking, &variant, &clues, 2), 1);
+        }
+    }
+
+    #[test]
+    fn test_sizes() {
+        for (box_rows, box_cols) in [(2,2), (2,3), (3,4), (4,4)] {
+            let variant = Variant::sized(Size::new(box_rows, box_cols).unwrap());
+            let digits = variant.size.digits();
+            let values = Array2D::filled_with(0, digits, digits);
+            let difficulty = (digits * digits / 2) as u8;
+
+            use std::time::Instant;
+            let now = Instant::now();
+
+            let (clues, solution) = generate_monitored(Backend::default(), &variant, &values, 42, difficulty, Symmetry::Rotational180, &mut Monitor::unobserved()).unwrap();
+
+            let elapsed = now.elapsed();
+            println!("{}x{} elapsed: {:.2?}", digits, digits, elapsed);
+
+            assert!(clues.elements_row_major_iter().filter(|value| **value == 0).count() >= difficulty as usize);
+            assert!(solution.elements_row_major_iter().all(|value| *value >= 1 && *value as usize <= digits));
+            assert_eq!(count_solutions(&variant, &clues, 2), 1);
+            assert_eq!(count_solutions_with(Backend::Backtracking, &variant, &clues, 2), 1);
+            assert_eq!(solve_with(Backend::Backtracking, &variant, &clues, Option::None).unwrap(), solution);
+        }
+
+        assert!(Size::new(1, 4).is_err());
+        assert!(Size::new(4, 5).is_err());
+        assert_eq!(Size::new(2, 3).unwrap().quad(3, 4), 3);
+        assert!(solve(&Variant::default(), &Array2D::filled_with(0, 4, 4), Option::None).is_err());
+        let minimal = generate_minimal(&Variant::sized(Size::new(2, 2).unwrap()), &Array2D::filled_with(0, 4, 4), 42, Symmetry::None).unwrap().0;
+        assert!(is_minimal(&Variant::sized(Size::new(2, 2).unwrap()), &minimal));
+    }
+
+    #[test]
+    fn test_jigsaw() {
+        use std::time::Instant;
+        let now = Instant::now();
+
+        let regions = generate_regions(Size::default(), 42).unwrap();
+        let variant = Variant::jigsaw(regions.clone());
+        assert!(variant.validate().is_ok());
+        assert_ne!(variant.fields(), Variant::default().fields());
+        let (clues, solution) = generate_monitored(Backend::default(), &variant, &Array2D::filled_with(0, 9, 9), 42, 50, Symmetry::None, &mut Monitor::unobserved()).unwrap();
+
+        let elapsed = now.elapsed();
+        println!("Elapsed: {:.2?}", elapsed);
+
+        for region in 0..9 {
+            let mut region_values:Vec<u8> = variant.fields().into_iter().filter(|(_,_,q)| *q == region).map(|(r,c,_)| solution[(r,c)]).collect();
+            region_values.sort();
+            assert_eq!(region_values, vec![1,2,3,4,5,6,7,8,9]);
+        }
+        assert_eq!(count_solutions(&variant, &clues, 2), 1);
+        assert_eq!(count_solutions_with(Backend::Backtracking, &variant, &clues, 2), 1);
+        assert_eq!(solve_with(Backend::Backtracking, &variant, &clues, Option::None).unwrap(), solution);
+
+        // regions must be contiguous and consist of nine fields
+        let mut split = regions.clone();
+        let (r,c) = (0..81).map(|idx| (idx / 9, idx % 9)).find(|(r,c)| split[*r][*c] == 4).unwrap();
+        split[r][c] = split[8][8];
+        assert!(Variant::jigsaw(split).validate().is_err());
+        let mut stripes:Vec<Vec<usize>> = (0..9).map(|r| vec![r; 9]).collect();
+        assert!(Variant::jigsaw(stripes.clone()).validate().is_ok());
+        stripes[0][0] = 2;
+        stripes[2][0] = 0;
+        assert!(Variant::jigsaw(stripes).validate().is_err());
+
+        let mut monitor = Monitor::new(CancellationToken::default(), |_| {});
+        monitor.token.cancel();
+        assert_eq!(generate_regions_monitored(Size::default(), 42, &mut monitor), Err("Generation cancelled".into()));
+    }
+
+    #[test]
+    fn test_extra_units() {
+        let x = Variant { diagonals: true, ..Variant::default() };
+        let windoku = Variant { windows: true, ..Variant::default() };
+        assert_eq!(x.extra_units(4, 4), vec![0, 1]);
+        assert_eq!(windoku.extra_units(5, 3), vec![2]);
+        assert!(windoku.extra_units(4, 2).is_empty());
+
+        // values that only conflict on a diagonal or in a window
+        let mut values = Array2D::filled_with(0, 9, 9);
+        values[(0,0)] = 1;
+        values[(4,4)] = 1;
+        assert!(solve(&Variant::default(), &values, Option::None).is_ok());
+        assert!(solve(&x, &values, Option::None).is_err());
+        let mut values = Array2D::filled_with(0, 9, 9);
+        values[(1,1)] = 1;
+        values[(3,3)] = 1;
+        assert!(solve(&Variant::default(), &values, Option::None).is_ok());
+        assert!(solve(&windoku, &values, Option::None).is_err());
+
+        let both = Variant { diagonals: true, windows: true, ..Variant::default() };
+        for variant in [x, windoku, both, Variant { windows: true, ..Variant::sized(Size::new(2, 3).unwrap()) }] {
+            let digits = variant.size.digits();
+            let difficulty = (digits * digits / 2) as u8;
+
+            use std::time::Instant;
+            let now = Instant::now();
+
+            let (clues, solution) = generate_monitored(Backend::default(), &variant, &Array2D::filled_with(0, digits, digits), 42, difficulty, Symmetry::None, &mut Monitor::unobserved()).unwrap();
+
+            let elapsed = now.elapsed();
+            println!("Elapsed: {:.2?}", elapsed);
+
+            for unit in 0..variant.extra_unit_count() {
+                let mut unit_values:Vec<u8> = variant.fields().into_iter()
+                    .filter(|(r,c,_)| variant.extra_units(*r, *c).contains(&unit))
+                    .map(|(r,c,_)| solution[(r,c)])
+                    .collect();
+                unit_values.sort();
+                assert_eq!(unit_values, (1..=digits as u8).collect::<Vec<u8>>());
+            }
+            assert_eq!(count_solutions(&variant, &clues, 2), 1);
+            assert_eq!(count_solutions_with(Backend::Backtracking, &variant, &clues, 2), 1);
+            assert_eq!(solve_with(Backend::Backtracking, &variant, &clues, Option::None).unwrap(), solution);
+        }
+    }
+
+    #[test]
+    fn test_negative_constraints() {
+        let knight = Variant { anti_knight: true, ..Variant::default() };
+        let king = Variant { anti_king: true, ..Variant::default() };
+        let non_consecutive = Variant { non_consecutive: true, ..Variant::default() };
+        let miracle = Variant { anti_knight: true, anti_king: true, non_consecutive: true, ..Variant::default() };
+        assert_eq!(knight.peers(0, 0), vec![(1,2), (2,1)]);
+        assert_eq!(non_consecutive.adjacent(0, 0), vec![(1,0), (0,1)]);
+        assert!(!knight.is_exact_cover());
+
+        // pairs of values that only conflict under one of the rules
+        for (variant, a, b) in [(&knight, (0,2,1), (2,3,1)), (&king, (2,2,1), (3,3,1)), (&non_consecutive, (0,0,1), (0,1,2))] {
+            let mut values = Array2D::filled_with(0, 9, 9);
+            values[(a.0, a.1)] = a.2;
+            values[(b.0, b.1)] = b.2;
+            assert!(solve(&Variant::default(), &values, Option::None).is_ok());
+            assert!(solve(variant, &values, Option::None).is_err());
+        }
+
+        for variant in [knight, king, non_consecutive, miracle] {
+            let (clues, solution) = generate_monitored(Backend::default(), &variant, &Array2D::filled_with(0, 9, 9), 42, 50, Symmetry::None, &mut Monitor::unobserved()).unwrap();
+            for (r,c,_) in variant.fields() {
+                assert!(variant.peers(r, c).iter().all(|peer| solution[*peer] != solution[(r,c)]));
+                assert!(variant.adjacent(r, c).iter().all(|adjacent| solution[*adjacent].abs_diff(solution[(r,c)]) != 1));
+            }
+            assert_eq!(count_solutions(&variant, &clues, 2), 1);
+            assert_eq!(solve(&variant, &clues, Option::None).unwrap(), solution);
+        }
+    }
 }
@@ -0,0 +1,166 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::playfield::Game;
+use crate::savegame::{self, SaveMetadata};
+
+const EXTENSION:&str = "json";
+
+/// A save slot in the savegame directory.
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct Slot {
+    pub name: String,
+    /// Seconds since the Unix epoch the slot was last saved, 0 if unknown.
+    pub last_played: u64,
+    /// Summary of the saved game, `None` if the save can't be read.
+    pub metadata: Option<SaveMetadata>,
+}
+
+/// Lists the save slots in `dir`, the most recently played first.
+/// Unreadable saves are listed without metadata, so they can still be deleted.
+pub fn list(dir:&Path) -> Result<Vec<Slot>, String> {
+    if !dir.exists() {
+        return Ok(vec![]);
+    }
+
+    let mut slots = fs::read_dir(dir).map_err(|e| e.to_string())?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|extension| extension == EXTENSION))
+        .filter_map(|path| path.file_stem().and_then(|stem| stem.to_str()).map(|name| read_slot(&path, name)))
+        .collect::<Vec<Slot>>();
+    slots.sort_by(|a, b| b.last_played.cmp(&a.last_played).then_with(|| a.name.cmp(&b.name)));
+    Ok(slots)
+}
+
+/// Returns the slot `name` along with its summary.
+pub fn info(dir:&Path, name:&str) -> Result<Slot, String> {
+    existing_slot_path(dir, name).map(|path| read_slot(&path, name))
+}
+
+// Reads the summary of a slot, it is computed from the game for saves without one
+fn read_slot(path:&Path, name:&str) -> Slot {
+    let save = fs::read_to_string(path).map_err(|e| e.to_string()).and_then(|content| {
+        savegame::decode(&content).map(|save| (content, save))
+    });
+    match save {
+        Ok((content, save)) => Slot {
+            name: name.into(),
+            last_played: save.saved_at,
+            metadata: save.metadata.or_else(|| Game::from_json(&content, Option::None).ok().map(|game| game.metadata())),
+        },
+        Err(_) => Slot {
+            name: name.into(),
+            last_played: 0,
+            metadata: Option::None,
+        },
+    }
+}
+
+/// Saves the game into the slot `name`, replacing an existing save of the same name.
+/// The save is written next to the slot first, so a crash while saving leaves the old save intact.
+pub fn save(dir:&Path, name:&str, game:&Game) -> Result<(), String> {
+    let path = slot_path(dir, name)?;
+    let content = game.to_json().map_err(|_| "serialization failed".to_string())?;
+    fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    let partial = path.with_extension("partial");
+    fs::write(&partial, content).map_err(|e| e.to_string())?;
+    fs::rename(partial, path).map_err(|e| e.to_string())
+}
+
+/// Loads the game saved in the slot `name`.
+pub fn load(dir:&Path, name:&str) -> Result<Game, String> {
+    let content = fs::read_to_string(existing_slot_path(dir, name)?).map_err(|e| e.to_string())?;
+    Game::from_json(&content, Option::None)
+}
+
+pub fn rename(dir:&Path, name:&str, new_name:&str) -> Result<(), String> {
+    let (from, to) = (existing_slot_path(dir, name)?, free_slot_path(dir, new_name)?);
+    fs::rename(from, to).map_err(|e| e.to_string())
+}
+
+pub fn duplicate(dir:&Path, name:&str, new_name:&str) -> Result<(), String> {
+    let (from, to) = (existing_slot_path(dir, name)?, free_slot_path(dir, new_name)?);
+    fs::copy(from, to).map(|_| ()).map_err(|e| e.to_string())
+}
+
+pub fn delete(dir:&Path, name:&str) -> Result<(), String> {
+    fs::remove_file(existing_slot_path(dir, name)?).map_err(|e| e.to_string())
+}
+
+// Slot names are plain file names, they must not point outside of the savegame directory
+fn slot_path(dir:&Path, name:&str) -> Result<PathBuf, String> {
+    let name = name.trim();
+    if name.is_empty() || name.starts_with('.') || name.contains(['/', '\\', ':']) {
+        return Err(format!("Invalid save name '{}'", name));
+    }
+    Ok(dir.join(format!("{}.{}", name, EXTENSION)))
+}
+
+fn existing_slot_path(dir:&Path, name:&str) -> Result<PathBuf, String> {
+    let path = slot_path(dir, name)?;
+    match path.exists() {
+        true => Ok(path),
+        false => Err(format!("Save '{}' not found", name)),
+    }
+}
+
+fn free_slot_path(dir:&Path, name:&str) -> Result<PathBuf, String> {
+    let path = slot_path(dir, name)?;
+    match path.exists() {
+        true => Err(format!("Save '{}' already exists", name)),
+        false => Ok(path),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_library() {
+        let dir = std::env::temp_dir().join(format!("sudoku-library-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        assert_eq!(list(&dir), Ok(vec![]));
+
+        let puzzle = "003020600900305001001806400008102900700000008006708200002609500800203009005010300";
+        let mut game = Game::from_puzzle_string(puzzle, Option::None).unwrap();
+        let _ = game.set_value(4, 0, 0, Option::None);
+        save(&dir, "first", &game).unwrap();
+        assert!(save(&dir, "../outside", &game).is_err());
+        assert!(save(&dir, "", &game).is_err());
+
+        let slots = list(&dir).unwrap();
+        assert_eq!(slots.len(), 1);
+        assert_eq!(slots[0].name, "first");
+        assert_eq!(info(&dir, "first"), Ok(slots[0].clone()));
+        assert!(info(&dir, "missing").is_err());
+        assert!(slots[0].last_played > 0);
+        let metadata = slots[0].metadata.clone().unwrap();
+        assert_eq!(metadata.progress, 2);
+        assert!(metadata.thumbnail.starts_with("4.3.2.6.."));
+
+        duplicate(&dir, "first", "second").unwrap();
+        assert!(duplicate(&dir, "first", "second").is_err());
+        rename(&dir, "second", "third").unwrap();
+        assert!(rename(&dir, "second", "fourth").is_err());
+        assert_eq!(load(&dir, "third").unwrap().get_value(0, 0), 4);
+
+        // saves without metadata are summarized from the game, unreadable ones are still listed
+        fs::write(dir.join("legacy.json"), serde_json::to_string(&game).unwrap()).unwrap();
+        fs::write(dir.join("broken.json"), "{").unwrap();
+        let slots = list(&dir).unwrap();
+        let names = slots.iter().map(|slot| slot.name.as_str()).collect::<Vec<&str>>();
+        assert_eq!(names.len(), 4);
+        assert_eq!(names[2..], ["broken", "legacy"]);
+        assert_eq!(slots[3].metadata, Option::Some(metadata));
+        assert_eq!(slots[2].metadata, Option::None);
+        assert!(load(&dir, "broken").is_err());
+
+        delete(&dir, "broken").unwrap();
+        assert!(delete(&dir, "broken").is_err());
+        assert_eq!(list(&dir).unwrap().len(), 3);
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,709 @@
+use array2d::Array2D;
+use bitvec::{order::Lsb0, view::BitView};
+use serde::{Serialize, Deserialize};
+
+use crate::engine::{Sudoku, FIELDS, VALUES_BIN};
+
+const ALL_VALUES:u16 = 0b111111111;
+
+/// Human solving techniques in the order they are tried by the logical solver.
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub enum Technique {
+    NakedSingle,
+    HiddenSingle,
+    LockedCandidates,
+    NakedPair,
+    HiddenPair,
+    NakedTriple,
+    HiddenTriple,
+    NakedQuad,
+    HiddenQuad,
+    XWing,
+    Swordfish,
+    XYWing,
+    Backtracking,
+}
+
+/// Difficulty bands, graded by the hardest technique required to solve a grid.
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Grade {
+    Easy,
+    Medium,
+    // quads used to be graded as Unfair
+    #[serde(alias = "Unfair")]
+    Hard,
+    Extreme,
+}
+
+impl Technique {
+    /// Score of a single step, following the step scores of HoDoKu.
+    pub fn score(&self) -> u32 {
+        match self {
+            Technique::NakedSingle => 4,
+            Technique::HiddenSingle => 14,
+            Technique::LockedCandidates => 50,
+            Technique::NakedPair => 60,
+            Technique::HiddenPair => 70,
+            Technique::NakedTriple => 80,
+            Technique::HiddenTriple => 100,
+            Technique::NakedQuad => 120,
+            Technique::HiddenQuad => 150,
+            Technique::XWing => 140,
+            Technique::Swordfish => 150,
+            Technique::XYWing => 160,
+            Technique::Backtracking => 10000,
+        }
+    }
+
+    pub fn grade(&self) -> Grade {
+        match self {
+            Technique::NakedSingle | Technique::HiddenSingle => Grade::Easy,
+            Technique::LockedCandidates | Technique::NakedPair | Technique::HiddenPair => Grade::Medium,
+            Technique::NakedTriple | Technique::HiddenTriple | Technique::NakedQuad | Technique::HiddenQuad
+            | Technique::XWing | Technique::Swordfish | Technique::XYWing => Grade::Hard,
+            Technique::Backtracking => Grade::Extreme,
+        }
+    }
+}
+
+/// Rating of a grid: the hardest technique needed, its grade and the sum of all step scores.
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct Rating {
+    pub grade: Grade,
+    pub technique: Option<Technique>,
+    pub score: u32,
+}
+
+#[derive(Clone, Copy, Serialize, Debug, PartialEq, Eq)]
+pub enum Unit {
+    Row(usize),
+    Col(usize),
+    Quad(usize),
+}
+
+/// A single step made by the logical solver.
+/// Cells are `(row, col)` pairs, placed and eliminated digits are 1-based `(row, col, digit)` triplets.
+#[derive(Clone, Serialize, Debug)]
+pub struct Deduction {
+    pub technique: Technique,
+    pub cells: Vec<(usize, usize)>,
+    pub units: Vec<Unit>,
+    pub placements: Vec<(usize, usize, u8)>,
+    pub eliminations: Vec<(usize, usize, u8)>,
+}
+
+/// Explanation of the next placement: the eliminations needed to find it, followed by the placing step.
+#[derive(Clone, Serialize, Debug)]
+pub struct Explanation {
+    pub deductions: Vec<Deduction>,
+}
+
+impl Explanation {
+    /// The hardest technique used in the explanation.
+    pub fn technique(&self) -> Technique {
+        self.deductions.iter()
+            .map(|deduction| deduction.technique)
+            .max_by_key(|technique| (technique.grade(), technique.score()))
+            .unwrap_or(Technique::Backtracking)
+    }
+
+    pub fn placement(&self) -> Option<(usize, usize, u8)> {
+        self.deductions.last().and_then(|deduction| deduction.placements.first().copied())
+    }
+}
+
+pub struct LogicSolution {
+    pub deductions: Vec<Deduction>,
+}
+
+impl LogicSolution {
+    pub fn rating(&self) -> Rating {
+        let technique = self.deductions.iter()
+            .map(|deduction| deduction.technique)
+            .max_by_key(|technique| (technique.grade(), technique.score()));
+        Rating {
+            grade: technique.map(|t| t.grade()).unwrap_or(Grade::Easy),
+            technique,
+            score: self.deductions.iter().map(|deduction| deduction.technique.score()).sum(),
+        }
+    }
+}
+
+struct LogicSolver {
+    sudoku: Sudoku,
+    // candidates per field index, 0 for set fields
+    candidates: [u16; 81],
+    units: Vec<(Unit, Vec<usize>)>,
+}
+
+fn to_rc(idx:usize) -> (usize, usize) {
+    let (r,c,_) = FIELDS[idx];
+    (r,c)
+}
+
+fn sees(a:usize, b:usize) -> bool {
+    let (ra, ca, qa) = FIELDS[a];
+    let (rb, cb, qb) = FIELDS[b];
+    a != b && (ra == rb || ca == cb || qa == qb)
+}
+
+fn digits(mask:u16) -> Vec<usize> {
+    mask.view_bits::<Lsb0>()[0..9].iter_ones().collect()
+}
+
+fn combinations(items:&[usize], k:usize) -> Vec<Vec<usize>> {
+    if k == 0 {
+        return vec![vec![]];
+    }
+    if items.len() < k {
+        return vec![];
+    }
+    let mut result:Vec<Vec<usize>> = combinations(&items[1..], k - 1).into_iter().map(|mut combination| {
+        combination.insert(0, items[0]);
+        combination
+    }).collect();
+    result.extend(combinations(&items[1..], k));
+    result
+}
+
+fn units() -> Vec<(Unit, Vec<usize>)> {
+    let mut units = Vec::new();
+    for i in 0..9 {
+        units.push((Unit::Quad(i), (0..81).filter(|idx| FIELDS[*idx].2 == i).collect()));
+    }
+    for i in 0..9 {
+        units.push((Unit::Row(i), (0..81).filter(|idx| FIELDS[*idx].0 == i).collect()));
+    }
+    for i in 0..9 {
+        units.push((Unit::Col(i), (0..81).filter(|idx| FIELDS[*idx].1 == i).collect()));
+    }
+    units
+}
+
+impl LogicSolver {
+    fn new(values:&Array2D<u8>) -> Result<LogicSolver, String> {
+        Sudoku::new(Option::Some(values)).map(|sudoku| {
+            let mut candidates = [0u16; 81];
+            for (idx, (r,c,q)) in FIELDS.into_iter().enumerate() {
+                if sudoku.values[(r,c)] == 0 {
+                    candidates[idx] = sudoku.get_possible_mask((r,c,q)) & ALL_VALUES;
+                }
+            }
+            LogicSolver {
+                sudoku,
+                candidates,
+                units: units(),
+            }
+        })
+    }
+
+    fn is_solved(&self) -> bool {
+        self.sudoku.values.elements_row_major_iter().all(|value| *value > 0)
+    }
+
+    fn has_contradiction(&self) -> bool {
+        (0..81).any(|idx| {
+            let (r,c) = to_rc(idx);
+            self.sudoku.values[(r,c)] == 0 && self.candidates[idx] == 0
+        })
+    }
+
+    fn solve(&mut self) -> Result<Vec<Deduction>, String> {
+        let mut deductions = Vec::new();
+        while !self.is_solved() {
+            if self.has_contradiction() {
+                return Err("No solution found".into());
+            }
+            match self.next_deduction() {
+                Some(deduction) => {
+                    self.apply(&deduction);
+                    deductions.push(deduction);
+                },
+                None => {
+                    deductions.push(self.backtrack()?);
+                }
+            }
+        }
+        Ok(deductions)
+    }
+
+    fn explain(&mut self) -> Result<Vec<Deduction>, String> {
+        if self.is_solved() {
+            return Err("Already solved".into());
+        }
+
+        let mut deductions = Vec::new();
+        loop {
+            if self.has_contradiction() {
+                return Err("No solution found".into());
+            }
+            match self.next_deduction() {
+                Some(deduction) => {
+                    let is_placement = !deduction.placements.is_empty();
+                    self.apply(&deduction);
+                    deductions.push(deduction);
+                    if is_placement {
+                        return Ok(deductions);
+                    }
+                },
+                None => {
+                    deductions.push(self.guess()?);
+                    return Ok(deductions);
+                }
+            }
+        }
+    }
+
+    fn next_deduction(&self) -> Option<Deduction> {
+        self.naked_single()
+            .or_else(|| self.hidden_single())
+            .or_else(|| self.locked_candidates())
+            .or_else(|| self.naked_subset(2, Technique::NakedPair))
+            .or_else(|| self.hidden_subset(2, Technique::HiddenPair))
+            .or_else(|| self.naked_subset(3, Technique::NakedTriple))
+            .or_else(|| self.hidden_subset(3, Technique::HiddenTriple))
+            .or_else(|| self.naked_subset(4, Technique::NakedQuad))
+            .or_else(|| self.hidden_subset(4, Technique::HiddenQuad))
+            .or_else(|| self.fish(2, Technique::XWing))
+            .or_else(|| self.fish(3, Technique::Swordfish))
+            .or_else(|| self.xy_wing())
+    }
+
+    fn apply(&mut self, deduction:&Deduction) {
+        for (r,c,digit) in deduction.placements.iter() {
+            self.place(r*9 + c, (*digit - 1) as usize);
+        }
+        for (r,c,digit) in deduction.eliminations.iter() {
+            self.candidates[r*9 + c] &= !VALUES_BIN[(*digit - 1) as usize];
+        }
+    }
+
+    fn place(&mut self, idx:usize, mov_zero_based:usize) {
+        self.sudoku.set_value(FIELDS[idx], mov_zero_based);
+        self.candidates[idx] = 0;
+        for peer in 0..81 {
+            if sees(idx, peer) {
+                self.candidates[peer] &= !VALUES_BIN[mov_zero_based];
+            }
+        }
+    }
+
+    fn backtrack(&mut self) -> Result<Deduction, String> {
+        let empty:Vec<usize> = (0..81).filter(|idx| self.sudoku.values[to_rc(*idx)] == 0).collect();
+        if !self.sudoku.solve(0) {
+            return Err("No solution found".into());
+        }
+        self.candidates = [0u16; 81];
+
+        Ok(Deduction {
+            technique: Technique::Backtracking,
+            cells: empty.iter().map(|idx| to_rc(*idx)).collect(),
+            units: vec![],
+            placements: empty.iter().map(|idx| {
+                let (r,c) = to_rc(*idx);
+                (r, c, self.sudoku.values[(r,c)])
+            }).collect(),
+            eliminations: vec![],
+        })
+    }
+
+    fn guess(&mut self) -> Result<Deduction, String> {
+        // backtracking only reveals the weakest field instead of the whole grid
+        let idx = (0..81)
+            .filter(|idx| self.sudoku.values[to_rc(*idx)] == 0)
+            .min_by_key(|idx| self.candidates[*idx].count_ones())
+            .ok_or("Already solved")?;
+        let mut sudoku = self.sudoku.clone();
+        if !sudoku.solve(0) {
+            return Err("No solution found".into());
+        }
+
+        let (r,c) = to_rc(idx);
+        let digit = sudoku.values[(r,c)];
+        self.place(idx, (digit - 1) as usize);
+        Ok(Deduction {
+            technique: Technique::Backtracking,
+            cells: vec![(r,c)],
+            units: vec![],
+            placements: vec![(r, c, digit)],
+            eliminations: vec![],
+        })
+    }
+
+    fn positions(&self, cells:&[usize], mov_zero_based:usize) -> Vec<usize> {
+        cells.iter()
+            .filter(|idx| self.candidates[**idx] & VALUES_BIN[mov_zero_based] > 0)
+            .copied()
+            .collect()
+    }
+
+    fn naked_single(&self) -> Option<Deduction> {
+        (0..81).find(|idx| self.candidates[*idx].count_ones() == 1).map(|idx| {
+            let (r,c) = to_rc(idx);
+            Deduction {
+                technique: Technique::NakedSingle,
+                cells: vec![(r,c)],
+                units: vec![],
+                placements: vec![(r, c, self.candidates[idx].trailing_zeros() as u8 + 1)],
+                eliminations: vec![],
+            }
+        })
+    }
+
+    fn hidden_single(&self) -> Option<Deduction> {
+        for (unit, cells) in self.units.iter() {
+            for mov_zero_based in 0..9 {
+                let positions = self.positions(cells, mov_zero_based);
+                if positions.len() == 1 {
+                    let (r,c) = to_rc(positions[0]);
+                    return Option::Some(Deduction {
+                        technique: Technique::HiddenSingle,
+                        cells: vec![(r,c)],
+                        units: vec![*unit],
+                        placements: vec![(r, c, (mov_zero_based + 1) as u8)],
+                        eliminations: vec![],
+                    });
+                }
+            }
+        }
+        Option::None
+    }
+
+    fn locked_candidates(&self) -> Option<Deduction> {
+        // pointing: candidates of a quad confined to one line,
+        // claiming: candidates of a line confined to one quad
+        for (unit_a, cells_a) in self.units.iter() {
+            for (unit_b, cells_b) in self.units.iter() {
+                let is_pair = matches!((unit_a, unit_b),
+                    (Unit::Quad(_), Unit::Row(_) | Unit::Col(_)) | (Unit::Row(_) | Unit::Col(_), Unit::Quad(_)));
+                if !is_pair {
+                    continue;
+                }
+
+                for mov_zero_based in 0..9 {
+                    let positions = self.positions(cells_a, mov_zero_based);
+                    if positions.len() < 2 || !positions.iter().all(|idx| cells_b.contains(idx)) {
+                        continue;
+                    }
+
+                    let eliminations:Vec<(usize, usize, u8)> = self.positions(cells_b, mov_zero_based).into_iter()
+                        .filter(|idx| !cells_a.contains(idx))
+                        .map(|idx| {
+                            let (r,c) = to_rc(idx);
+                            (r, c, (mov_zero_based + 1) as u8)
+                        })
+                        .collect();
+                    if !eliminations.is_empty() {
+                        return Option::Some(Deduction {
+                            technique: Technique::LockedCandidates,
+                            cells: positions.into_iter().map(to_rc).collect(),
+                            units: vec![*unit_a, *unit_b],
+                            placements: vec![],
+                            eliminations,
+                        });
+                    }
+                }
+            }
+        }
+        Option::None
+    }
+
+    fn naked_subset(&self, size:usize, technique:Technique) -> Option<Deduction> {
+        for (unit, cells) in self.units.iter() {
+            let subset_cells:Vec<usize> = cells.iter()
+                .filter(|idx| {
+                    let count = self.candidates[**idx].count_ones() as usize;
+                    count >= 2 && count <= size
+                })
+                .copied()
+                .collect();
+
+            for combination in combinations(&subset_cells, size) {
+                let union = combination.iter().fold(0u16, |acc, idx| acc | self.candidates[*idx]);
+                if union.count_ones() as usize != size {
+                    continue;
+                }
+
+                let mut eliminations = Vec::new();
+                for idx in cells.iter().filter(|idx| !combination.contains(idx)) {
+                    let (r,c) = to_rc(*idx);
+                    for mov_zero_based in digits(self.candidates[*idx] & union) {
+                        eliminations.push((r, c, (mov_zero_based + 1) as u8));
+                    }
+                }
+                if !eliminations.is_empty() {
+                    return Option::Some(Deduction {
+                        technique,
+                        cells: combination.into_iter().map(to_rc).collect(),
+                        units: vec![*unit],
+                        placements: vec![],
+                        eliminations,
+                    });
+                }
+            }
+        }
+        Option::None
+    }
+
+    fn hidden_subset(&self, size:usize, technique:Technique) -> Option<Deduction> {
+        for (unit, cells) in self.units.iter() {
+            let subset_digits:Vec<usize> = (0..9)
+                .filter(|mov_zero_based| {
+                    let count = self.positions(cells, *mov_zero_based).len();
+                    count >= 2 && count <= size
+                })
+                .collect();
+
+            for combination in combinations(&subset_digits, size) {
+                let mut subset_cells:Vec<usize> = combination.iter()
+                    .flat_map(|mov_zero_based| self.positions(cells, *mov_zero_based))
+                    .collect();
+                subset_cells.sort();
+                subset_cells.dedup();
+                if subset_cells.len() != size {
+                    continue;
+                }
+
+                let digits_mask = combination.iter().fold(0u16, |acc, mov_zero_based| acc | VALUES_BIN[*mov_zero_based]);
+                let mut eliminations = Vec::new();
+                for idx in subset_cells.iter() {
+                    let (r,c) = to_rc(*idx);
+                    for mov_zero_based in digits(self.candidates[*idx] & !digits_mask) {
+                        eliminations.push((r, c, (mov_zero_based + 1) as u8));
+                    }
+                }
+                if !eliminations.is_empty() {
+                    return Option::Some(Deduction {
+                        technique,
+                        cells: subset_cells.into_iter().map(to_rc).collect(),
+                        units: vec![*unit],
+                        placements: vec![],
+                        eliminations,
+                    });
+                }
+            }
+        }
+        Option::None
+    }
+
+    fn fish(&self, size:usize, technique:Technique) -> Option<Deduction> {
+        for rows_as_base in [true, false] {
+            let field = |base:usize, cover:usize| match rows_as_base {
+                true => base*9 + cover,
+                false => cover*9 + base,
+            };
+            let unit = |i:usize, is_base:bool| match rows_as_base == is_base {
+                true => Unit::Row(i),
+                false => Unit::Col(i),
+            };
+
+            for (mov_zero_based, mov_bin) in VALUES_BIN.iter().copied().enumerate().take(9) {
+                // positions of the digit within every base line, as a bitmask over the cover lines
+                let masks:Vec<u16> = (0..9).map(|base| {
+                    (0..9).filter(|cover| self.candidates[field(base, *cover)] & mov_bin > 0)
+                        .fold(0u16, |acc, cover| acc | (1 << cover))
+                }).collect();
+                let bases:Vec<usize> = (0..9).filter(|base| {
+                    let count = masks[*base].count_ones() as usize;
+                    count >= 2 && count <= size
+                }).collect();
+
+                for combination in combinations(&bases, size) {
+                    let covers_mask = combination.iter().fold(0u16, |acc, base| acc | masks[*base]);
+                    if covers_mask.count_ones() as usize != size {
+                        continue;
+                    }
+                    let covers = digits(covers_mask);
+
+                    let mut eliminations = Vec::new();
+                    for base in (0..9).filter(|base| !combination.contains(base)) {
+                        for cover in covers.iter() {
+                            let idx = field(base, *cover);
+                            if self.candidates[idx] & mov_bin > 0 {
+                                let (r,c) = to_rc(idx);
+                                eliminations.push((r, c, (mov_zero_based + 1) as u8));
+                            }
+                        }
+                    }
+                    if !eliminations.is_empty() {
+                        let mut cells = Vec::new();
+                        for base in combination.iter() {
+                            for cover in covers.iter() {
+                                let idx = field(*base, *cover);
+                                if self.candidates[idx] & mov_bin > 0 {
+                                    cells.push(to_rc(idx));
+                                }
+                            }
+                        }
+                        let mut units:Vec<Unit> = combination.iter().map(|base| unit(*base, true)).collect();
+                        units.extend(covers.iter().map(|cover| unit(*cover, false)));
+
+                        return Option::Some(Deduction {
+                            technique,
+                            cells,
+                            units,
+                            placements: vec![],
+                            eliminations,
+                        });
+                    }
+                }
+            }
+        }
+        Option::None
+    }
+
+    fn xy_wing(&self) -> Option<Deduction> {
+        let bivalue:Vec<usize> = (0..81).filter(|idx| self.candidates[*idx].count_ones() == 2).collect();
+        for pivot in bivalue.iter() {
+            let pivot_poss = self.candidates[*pivot];
+            for pincer_a in bivalue.iter().filter(|idx| sees(*pivot, **idx)) {
+                let poss_a = self.candidates[*pincer_a];
+                let z = poss_a & !pivot_poss;
+                if (poss_a & pivot_poss).count_ones() != 1 || z.count_ones() != 1 {
+                    continue;
+                }
+
+                let poss_b = (pivot_poss & !poss_a) | z;
+                for pincer_b in bivalue.iter().filter(|idx| sees(*pivot, **idx) && self.candidates[**idx] == poss_b) {
+                    let mov_zero_based = z.trailing_zeros() as usize;
+                    let eliminations:Vec<(usize, usize, u8)> = (0..81)
+                        .filter(|idx| {
+                            *idx != *pivot && sees(*pincer_a, *idx) && sees(*pincer_b, *idx)
+                                && self.candidates[*idx] & z > 0
+                        })
+                        .map(|idx| {
+                            let (r,c) = to_rc(idx);
+                            (r, c, (mov_zero_based + 1) as u8)
+                        })
+                        .collect();
+                    if !eliminations.is_empty() {
+                        return Option::Some(Deduction {
+                            technique: Technique::XYWing,
+                            cells: vec![to_rc(*pivot), to_rc(*pincer_a), to_rc(*pincer_b)],
+                            units: vec![],
+                            placements: vec![],
+                            eliminations,
+                        });
+                    }
+                }
+            }
+        }
+        Option::None
+    }
+}
+
+/// Solves a given Sudoku grid like a human would, by applying the techniques of [`Technique`] in order.
+/// Backtracking is only used as a fallback once no technique makes progress anymore.
+/// As a result, the ordered list of deductions is returned, their placements fill every empty field.
+pub fn solve(values:&Array2D<u8>) -> Result<LogicSolution, String> {
+    LogicSolver::new(values)?.solve().map(|deductions| LogicSolution { deductions })
+}
+
+/// Rates a given Sudoku grid by the hardest technique the logical solver needs to solve it.
+pub fn rate(values:&Array2D<u8>) -> Result<Rating, String> {
+    solve(values).map(|solution| solution.rating())
+}
+
+/// Explains the next placement in a given Sudoku grid.
+/// Returns an error if the grid is already solved or has no solution.
+pub fn explain(values:&Array2D<u8>) -> Result<Explanation, String> {
+    LogicSolver::new(values)?.explain().map(|deductions| Explanation { deductions })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{self, Variant};
+
+    fn place_all(values:&Array2D<u8>, solution:&LogicSolution) -> Array2D<u8> {
+        let mut values = values.clone();
+        solution.deductions.iter()
+            .flat_map(|deduction| deduction.placements.iter())
+            .for_each(|(r,c,digit)| values[(*r,*c)] = *digit);
+        values
+    }
+
+    const PUZZLES:[&str; 4] = [
+        "003020600900305001001806400008102900700000008006708200002609500800203009005010300",
+        "000000010400000000020000000000050407008000300001090000300400200050100000000806000",
+        "800000000003600000070090200050007000000045700000100030001000068008500010090000400",
+        "100007090030020008009600500005300900010080002600004000300000010040000007007000300",
+    ];
+
+    #[test]
+    fn test_solve_singles_only() {
+        let values = engine::parse_grid(PUZZLES[0]).unwrap();
+        let result = solve(&values);
+        assert!(result.is_ok());
+
+        let solution = result.unwrap();
+        assert!(place_all(&values, &solution).elements_row_major_iter().all(|i| *i > 0));
+        assert!(solution.deductions.iter().all(|d| matches!(d.technique, Technique::NakedSingle | Technique::HiddenSingle)));
+    }
+
+    #[test]
+    fn test_deductions_are_sound() {
+        for puzzle in PUZZLES {
+            let values = engine::parse_grid(puzzle).unwrap();
+            let expected = engine::solve(&Variant::default(), &values, Option::None).unwrap();
+
+            use std::time::Instant;
+            let now = Instant::now();
+
+            let solution = solve(&values).unwrap();
+
+            let elapsed = now.elapsed();
+            println!("Elapsed: {:.2?}", elapsed);
+
+            assert_eq!(place_all(&values, &solution), expected);
+            for deduction in solution.deductions.iter() {
+                for (r,c,digit) in deduction.placements.iter() {
+                    assert_eq!(expected[(*r,*c)], *digit);
+                }
+                for (r,c,digit) in deduction.eliminations.iter() {
+                    assert_ne!(expected[(*r,*c)], *digit);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_rate() {
+        let easy = rate(&engine::parse_grid(PUZZLES[0]).unwrap()).unwrap();
+        assert_eq!(easy.grade, Grade::Easy);
+        assert!(easy.score > 0);
+
+        let hard = rate(&engine::parse_grid(PUZZLES[2]).unwrap()).unwrap();
+        assert!(hard.grade > Grade::Easy);
+        assert!(hard.score > easy.score);
+        assert_eq!(Technique::HiddenQuad.grade(), Grade::Hard);
+        assert_eq!(serde_json::from_str::<Grade>("\"Unfair\"").unwrap(), Grade::Hard);
+
+        let solved = rate(&engine::solve(&Variant::default(), &engine::parse_grid(PUZZLES[0]).unwrap(), Option::None).unwrap()).unwrap();
+        assert_eq!(solved.technique, Option::None);
+        assert_eq!(solved.score, 0);
+    }
+
+    #[test]
+    fn test_explain() {
+        for puzzle in PUZZLES {
+            let values = engine::parse_grid(puzzle).unwrap();
+            let expected = engine::solve(&Variant::default(), &values, Option::None).unwrap();
+
+            let explanation = explain(&values).unwrap();
+            assert!(explanation.deductions.iter().rev().skip(1).all(|d| d.placements.is_empty()));
+
+            let (r,c,digit) = explanation.placement().unwrap();
+            assert_eq!(values[(r,c)], 0);
+            assert_eq!(expected[(r,c)], digit);
+        }
+
+        assert!(explain(&engine::solve(&Variant::default(), &engine::parse_grid(PUZZLES[0]).unwrap(), Option::None).unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_combinations() {
+        assert_eq!(combinations(&[1,2,3], 2), vec![vec![1,2], vec![1,3], vec![2,3]]);
+        assert_eq!(combinations(&[1,2], 3).len(), 0);
+    }
+}
@@ -3,9 +3,26 @@
 
 mod playfield;
 mod engine;
+mod logic;
+mod dlx;
+mod savegame;
+mod library;
+mod statistics;
+use std::path::PathBuf;
 use std::sync::Mutex;
-use playfield::Game;
-use tauri::Window;
+use std::time::{Duration, Instant};
+use playfield::{EditorStatus, Game, GameState, MistakeCheck};
+use engine::{Backend, CancellationToken, Monitor, Size, Symmetry, Variant};
+use logic::{Grade, Rating};
+use array2d::Array2D;
+use library::Slot;
+use statistics::{GameRecord, Statistics, Summary};
+use tauri::{AppHandle, Manager, Window, WindowEvent};
+
+const PROGRESS_INTERVAL:Duration = Duration::from_millis(100);
+// changes within the interval are autosaved together
+const AUTOSAVE_INTERVAL:Duration = Duration::from_secs(2);
+const RECOVERY_SLOT:&str = "recovery";
 
 #[derive(serde::Serialize, Clone, Copy)]
 struct Cell {
@@ -16,6 +33,17 @@ struct Cell {
 // Mutex for interior mutability
 struct PlayfieldState {
     playfield: Mutex<Game>,
+    generation: Mutex<Option<CancellationToken>>,
+}
+
+impl PlayfieldState {
+    // Cancels the running generation, if any. Commands that replace the game call it first, so they aren't overwritten.
+    fn cancel_generation(&self) -> bool {
+        self.generation.lock().unwrap()
+            .take()
+            .map(|running| running.cancel())
+            .is_some()
+    }
 }
 
 struct Request {
@@ -24,6 +52,41 @@ struct Request {
     include_solution_count: bool,
 }
 
+// Settings of a generated grid, the flags that are left out default to a classic grid
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GenerateOptions {
+    difficulty: u8,
+    seed: u64,
+    fix_result: bool,
+    #[serde(default)]
+    grade: Option<Grade>, // if this is set, difficulty is ignored and a grid of the given grade is generated
+    #[serde(default)]
+    symmetry: Option<Symmetry>,
+    #[serde(default)]
+    minimal: bool, // if this is set, difficulty is ignored and clues are removed until the grid is minimal
+    #[serde(default)]
+    killer: bool, // if this is set, a killer grid with a random cage layout is generated
+    #[serde(default)]
+    box_rows: Option<usize>, // if these are set, a grid with quads of the given dimensions is generated
+    #[serde(default)]
+    box_cols: Option<usize>,
+    #[serde(default)]
+    jigsaw: bool, // if this is set, the grid is divided into random irregular regions instead of quads
+    #[serde(default)]
+    diagonals: bool, // if this is set, both main diagonals must hold every digit once (X-Sudoku)
+    #[serde(default)]
+    windows: bool, // if this is set, the hyper boxes between the quads must hold every digit once (Windoku)
+    #[serde(default)]
+    anti_knight: bool, // if this is set, equal digits must not be a chess knight's move apart
+    #[serde(default)]
+    anti_king: bool, // if this is set, equal digits must not be a chess king's move apart
+    #[serde(default)]
+    non_consecutive: bool, // if this is set, orthogonally adjacent fields must not hold consecutive digits
+    #[serde(default)]
+    backend: Option<Backend>, // the solver that checks the uniqueness of the clues, Dancing Links if not set
+}
+
 #[tauri::command]
 fn serialize(
     state: tauri::State<'_, PlayfieldState>,
@@ -50,6 +113,69 @@ fn toggle_note(
     )
 }
 
+#[tauri::command]
+fn fill_candidates(
+    state: tauri::State<'_, PlayfieldState>,
+    window: Window,
+    keep_notes: Option<bool>, // if this is set, cells that already have notes are left untouched
+) -> Result<(),String> {
+    let mut playfield = state.playfield.lock().unwrap();
+    playfield.fill_candidates(
+        keep_notes.unwrap_or(false),
+        Option::Some(&Request {
+            window,
+            include_clue_count:false,
+            include_solution_count:false,
+        }), 
+    )
+}
+
+#[tauri::command]
+fn toggle_corner_note(
+    state: tauri::State<'_, PlayfieldState>,
+    window: Window,
+    row:usize, col:usize, 
+    value:usize
+) -> Result<(),String> {
+    let mut playfield = state.playfield.lock().unwrap();
+    playfield.toggle_corner_note(
+        row, col, value,
+        Option::Some(&Request {
+            window,
+            include_clue_count:false,
+            include_solution_count:false,
+        }), 
+    )
+}
+
+#[tauri::command]
+fn set_color(
+    state: tauri::State<'_, PlayfieldState>,
+    window: Window,
+    row:usize, col:usize, 
+    color:Option<u8>
+) -> Result<(),String> {
+    let mut playfield = state.playfield.lock().unwrap();
+    playfield.set_color(
+        row, col, color,
+        Option::Some(&Request {
+            window,
+            include_clue_count:false,
+            include_solution_count:false,
+        }), 
+    )
+}
+
+#[tauri::command]
+fn set_auto_eliminate(
+    state: tauri::State<'_, PlayfieldState>,
+    enabled: bool,
+) -> Result<(),String> {
+    let mut playfield = state.playfield.lock().unwrap();
+    playfield.set_auto_eliminate(enabled);
+    Ok(())
+}
+
 #[tauri::command]
 fn increment_timer(
     state: tauri::State<'_, PlayfieldState>,
@@ -61,6 +187,7 @@ fn increment_timer(
 #[tauri::command]
 fn hint(
     state: tauri::State<'_, PlayfieldState>,
+    app: AppHandle,
     window: Window,
     include_clue_count: bool,
     include_solution_count: bool,
@@ -70,7 +197,24 @@ fn hint(
         window,
         include_clue_count,
         include_solution_count,
-    }))
+    })).map(|_| track_result(&app, &mut playfield))
+}
+
+#[tauri::command]
+fn explain_hint(
+    state: tauri::State<'_, PlayfieldState>,
+    app: AppHandle,
+    window: Window,
+    reveal: bool,
+    include_clue_count: bool,
+    include_solution_count: bool,
+) -> Result<(),String> {
+    let mut playfield = state.playfield.lock().unwrap();
+    playfield.explain_hint(reveal, Option::Some(&Request {
+        window,
+        include_clue_count,
+        include_solution_count,
+    })).map(|_| track_result(&app, &mut playfield))
 }
 
 #[tauri::command]
@@ -92,40 +236,462 @@ fn unhint(
 #[tauri::command]
 fn deserialize(
     state: tauri::State<'_, PlayfieldState>,
+    app: AppHandle,
     window: Window,
     msg: String,
     include_clue_count: bool,
     include_solution_count: bool,
 ) -> Result<u8, String> {
+    let game = Game::from_json(&msg, Option::None)?;
+    state.cancel_generation();
     let mut playfield = state.playfield.lock().unwrap();
-    *playfield = Game::from_json(&msg, Option::Some(&Request {
+    track_abandoned(&app, &playfield);
+    *playfield = game;
+    playfield.emit_update_event(&Request {
         window,
         include_clue_count,
         include_solution_count,
-    }));
+    });
+    Ok(playfield.get_state() as u8)
+}
+
+fn app_data_dir(app:&AppHandle) -> Result<PathBuf, String> {
+    app.path_resolver().app_data_dir().ok_or("App data directory not available".into())
+}
+
+// Directory of the save slots within the app data directory, the recovery slot is kept apart
+fn savegame_dir(app:&AppHandle) -> Result<PathBuf, String> {
+    app_data_dir(app).map(|dir| dir.join("savegames"))
+}
+
+// Writes the game to the recovery slot if it changed since the last autosave.
+// Solved games whose result is recorded have nothing left to resume, their slot is cleared instead.
+fn autosave(app:&AppHandle) -> Result<(), String> {
+    let state = app.state::<PlayfieldState>();
+    let mut playfield = state.playfield.lock().unwrap();
+    if !playfield.has_unsaved_changes() {
+        return Ok(());
+    }
+    match playfield.get_state() == GameState::Solved && playfield.pending_result().is_none() {
+        true => clear_recovery(app),
+        false => library::save(&app_data_dir(app)?, RECOVERY_SLOT, &playfield)?,
+    }
+    playfield.mark_saved();
+    Ok(())
+}
+
+// Removes the recovery slot, there may be none
+fn clear_recovery(app:&AppHandle) {
+    let _ = app_data_dir(app).and_then(|dir| library::delete(&dir, RECOVERY_SLOT));
+}
+
+fn statistics_path(app:&AppHandle) -> Result<PathBuf, String> {
+    app_data_dir(app).map(|dir| dir.join("statistics.json"))
+}
+
+// Adds the result of a solved puzzle to the statistics. The move that solved the puzzle succeeded anyway,
+// so failures are reported on their own and the result stays pending for the next attempt.
+fn track_result(app:&AppHandle, playfield:&mut Game) {
+    let Some(record) = playfield.pending_result() else {
+        return;
+    };
+    match record_result(app, record) {
+        Ok(()) => playfield.mark_recorded(),
+        Err(e) => {
+            let _ = app.emit_all("statisticsError", e);
+        },
+    }
+}
+
+// Adds a started puzzle that is about to be replaced to the statistics as abandoned, failures are reported on their own
+fn track_abandoned(app:&AppHandle, playfield:&Game) {
+    if let Some(record) = playfield.abandoned_result() {
+        let _ = record_result(app, record).map_err(|e| app.emit_all("statisticsError", e));
+    }
+}
+
+fn record_result(app:&AppHandle, record:GameRecord) -> Result<(), String> {
+    let path = statistics_path(app)?;
+    let mut statistics = Statistics::load(&path)?;
+    statistics.record(record);
+    statistics.save(&path)
+}
+
+#[tauri::command]
+fn get_statistics(
+    app: AppHandle,
+) -> Result<Summary, String> {
+    Statistics::load(&statistics_path(&app)?).map(|statistics| statistics.summary())
+}
+
+#[tauri::command]
+fn get_game_records(
+    app: AppHandle,
+) -> Result<Vec<GameRecord>, String> {
+    Statistics::load(&statistics_path(&app)?).map(|statistics| statistics.games().to_vec())
+}
+
+#[tauri::command]
+fn reset_statistics(
+    app: AppHandle,
+) -> Result<(), String> {
+    Statistics::default().save(&statistics_path(&app)?)
+}
+
+#[tauri::command]
+fn recovery_info(
+    app: AppHandle,
+) -> Result<Option<Slot>, String> {
+    Ok(library::info(&app_data_dir(&app)?, RECOVERY_SLOT).ok())
+}
+
+#[tauri::command]
+fn restore_recovery(
+    state: tauri::State<'_, PlayfieldState>,
+    app: AppHandle,
+    window: Window,
+    include_clue_count: bool,
+    include_solution_count: bool,
+) -> Result<u8, String> {
+    let game = library::load(&app_data_dir(&app)?, RECOVERY_SLOT)?;
+    state.cancel_generation();
+    let mut playfield = state.playfield.lock().unwrap();
+    track_abandoned(&app, &playfield);
+    *playfield = game;
+    playfield.emit_update_event(&Request {
+        window,
+        include_clue_count,
+        include_solution_count,
+    });
+    playfield.mark_saved();
     Ok(playfield.get_state() as u8)
 }
 
 #[tauri::command]
-fn generate(
+fn discard_recovery(
+    app: AppHandle,
+) -> Result<(), String> {
+    library::delete(&app_data_dir(&app)?, RECOVERY_SLOT)
+}
+
+#[tauri::command]
+fn list_saves(
+    app: AppHandle,
+) -> Result<Vec<Slot>, String> {
+    library::list(&savegame_dir(&app)?)
+}
+
+#[tauri::command]
+fn save_game(
+    state: tauri::State<'_, PlayfieldState>,
+    app: AppHandle,
+    name: String,
+) -> Result<(), String> {
+    let playfield = state.playfield.lock().unwrap();
+    library::save(&savegame_dir(&app)?, &name, &playfield)
+}
+
+#[tauri::command]
+fn load_game(
     state: tauri::State<'_, PlayfieldState>,
+    app: AppHandle,
     window: Window,
-    difficulty: u8, 
-    seed: u64,
+    name: String,
     include_clue_count: bool,
     include_solution_count: bool,
-    fix_result: bool,
+) -> Result<u8, String> {
+    let game = library::load(&savegame_dir(&app)?, &name)?;
+    state.cancel_generation();
+    let mut playfield = state.playfield.lock().unwrap();
+    track_abandoned(&app, &playfield);
+    *playfield = game;
+    playfield.emit_update_event(&Request {
+        window,
+        include_clue_count,
+        include_solution_count,
+    });
+    Ok(playfield.get_state() as u8)
+}
+
+#[tauri::command]
+fn rename_save(
+    app: AppHandle,
+    name: String,
+    new_name: String,
+) -> Result<(), String> {
+    library::rename(&savegame_dir(&app)?, &name, &new_name)
+}
+
+#[tauri::command]
+fn duplicate_save(
+    app: AppHandle,
+    name: String,
+    new_name: String,
+) -> Result<(), String> {
+    library::duplicate(&savegame_dir(&app)?, &name, &new_name)
+}
+
+#[tauri::command]
+fn delete_save(
+    app: AppHandle,
+    name: String,
 ) -> Result<(), String> {
-    state.playfield.lock().unwrap().generate(
-        difficulty, 
-        seed, 
+    library::delete(&savegame_dir(&app)?, &name)
+}
+
+#[tauri::command]
+fn import_puzzle(
+    state: tauri::State<'_, PlayfieldState>,
+    app: AppHandle,
+    window: Window,
+    msg: String,
+    include_clue_count: bool,
+    include_solution_count: bool,
+) -> Result<u8, String> {
+    let game = Game::from_puzzle_string(&msg, Option::None)?;
+    state.cancel_generation();
+    let mut playfield = state.playfield.lock().unwrap();
+    track_abandoned(&app, &playfield);
+    *playfield = game;
+    playfield.emit_update_event(&Request {
+        window,
+        include_clue_count,
+        include_solution_count,
+    });
+    Ok(playfield.get_state() as u8)
+}
+
+#[tauri::command]
+fn export_puzzle(
+    state: tauri::State<'_, PlayfieldState>,
+    include_solution: bool,
+) -> Result<String, String> {
+    state.playfield.lock().unwrap().to_puzzle_string(include_solution)
+}
+
+// Generation runs on a worker thread, the playfield is only locked to read the preset and to install the result
+#[tauri::command]
+async fn generate(
+    state: tauri::State<'_, PlayfieldState>,
+    app: AppHandle,
+    window: Window,
+    include_clue_count: bool,
+    include_solution_count: bool,
+    options: GenerateOptions,
+) -> Result<(), String> {
+    let GenerateOptions { difficulty, seed, fix_result, grade, minimal, killer, jigsaw, .. } = options;
+    let symmetry = options.symmetry.unwrap_or_default();
+    let backend = options.backend.unwrap_or_default();
+    let size = match (options.box_rows, options.box_cols) {
+        (Some(rows), Some(cols)) => Size::new(rows, cols)?,
+        (None, None) => Size::default(),
+        _ => return Err("Both quad dimensions must be given".into()),
+    };
+    let rules = Variant {
+        diagonals: options.diagonals,
+        windows: options.windows,
+        anti_knight: options.anti_knight,
+        anti_king: options.anti_king,
+        non_consecutive: options.non_consecutive,
+        ..Variant::sized(size)
+    };
+    if grade.is_some() && (!rules.is_classic() || jigsaw) {
+        return Err("Only classic grids can be generated by grade".into());
+    }
+    // a new generation replaces the running one
+    let token = CancellationToken::default();
+    if let Some(running) = state.generation.lock().unwrap().replace(token.clone()) {
+        running.cancel();
+    }
+    let values = {
+        let playfield = state.playfield.lock().unwrap();
+        match playfield.get_variant().size == size {
+            true => playfield.get_values(),
+            false => Array2D::filled_with(0, size.digits(), size.digits()),
+        }
+    };
+
+    let progress_window = window.clone();
+    let worker_token = token.clone();
+    let result = tauri::async_runtime::spawn_blocking(move || {
+        let mut last_emitted:Option<Instant> = Option::None;
+        let mut monitor = Monitor::new(worker_token, |progress| {
+            if last_emitted.is_none_or(|instant| instant.elapsed() >= PROGRESS_INTERVAL) {
+                let _ = progress_window.emit("generateProgress", progress);
+                last_emitted = Option::Some(Instant::now());
+            }
+        });
+        let difficulty_option = match minimal {
+            true => Option::None,
+            false => Option::Some(difficulty),
+        };
+        let template = match jigsaw {
+            true => Variant { regions: Option::Some(engine::generate_regions_monitored(size, seed, &mut monitor)?), ..rules },
+            false => rules,
+        };
+        match (grade, killer) {
+            (Some(_), true) => Err("Killer grids can't be generated by grade".into()),
+            (Some(g), false) => engine::generate_rated_monitored(&values, seed, g, symmetry, &mut monitor)
+                .map(|(clues, solution, rating)| (Variant::default(), clues, solution, Option::Some(rating))),
+            (None, true) => engine::generate_killer_monitored(backend, &template, &values, seed, difficulty_option, symmetry, &mut monitor)
+                .map(|(variant, clues, solution)| (variant, clues, solution, Option::None)),
+            (None, false) => match difficulty_option {
+                Some(d) => engine::generate_monitored(backend, &template, &values, seed, d, symmetry, &mut monitor),
+                None => engine::generate_minimal_monitored(backend, &template, &values, seed, symmetry, &mut monitor),
+            }.map(|(clues, solution)| {
+                let rating = match template.is_classic() {
+                    true => logic::rate(&clues).ok(),
+                    false => Option::None,
+                };
+                (template, clues, solution, rating)
+            }),
+        }
+    }).await.map_err(|_| "Generation failed".to_string())?;
+
+    // the generation stays locked while installing, so a command replacing the game either cancels it before or comes after
+    let mut generation = state.generation.lock().unwrap();
+    if !generation.as_ref().is_some_and(|running| running.is_same(&token)) {
+        return Err("Generation cancelled".into());
+    }
+    *generation = Option::None;
+
+    let (variant, clues, solution, rating) = result?;
+    // symmetric orbits, cages and presets make the removed clues differ from the requested difficulty
+    let removed_count = clues.elements_row_major_iter().filter(|value| **value == 0).count() as u8;
+    let mut playfield = state.playfield.lock().unwrap();
+    track_abandoned(&app, &playfield);
+    playfield.install_generated(
+        (clues, solution, rating),
+        variant,
+        removed_count,
+        seed,
         Option::Some(&Request {
             window,
             include_clue_count,
             include_solution_count,
         }),
         fix_result,
-    ).map(|_| ())
+    );
+    Ok(())
+}
+
+#[tauri::command]
+fn cancel_generation(
+    state: tauri::State<'_, PlayfieldState>,
+) -> Result<(), String> {
+    match state.cancel_generation() {
+        true => Ok(()),
+        false => Err("No generation running".into()),
+    }
+}
+
+#[tauri::command]
+fn rate(
+    state: tauri::State<'_, PlayfieldState>,
+) -> Result<Rating, String> {
+    state.playfield.lock().unwrap().rate()
+}
+
+#[tauri::command]
+fn set_variant(
+    state: tauri::State<'_, PlayfieldState>,
+    window: Window,
+    variant: Variant,
+    include_clue_count: bool,
+    include_solution_count: bool,
+) -> Result<(), String> {
+    state.playfield.lock().unwrap().set_variant(
+        variant,
+        Option::Some(&Request {
+            window,
+            include_clue_count,
+            include_solution_count,
+        }),
+    )
+}
+
+#[tauri::command]
+fn set_mistake_check(
+    state: tauri::State<'_, PlayfieldState>,
+    window: Window,
+    mistake_check: MistakeCheck,
+    include_clue_count: bool,
+    include_solution_count: bool,
+) {
+    state.playfield.lock().unwrap().set_mistake_check(
+        mistake_check,
+        Option::Some(&Request {
+            window,
+            include_clue_count,
+            include_solution_count,
+        }),
+    )
+}
+
+#[tauri::command]
+fn redundant_clues(
+    state: tauri::State<'_, PlayfieldState>,
+) -> Result<Vec<(usize, usize)>, String> {
+    state.playfield.lock().unwrap().redundant_clues()
+}
+
+#[tauri::command]
+fn check_notes(
+    state: tauri::State<'_, PlayfieldState>,
+) -> Result<Vec<(usize, usize)>, String> {
+    state.playfield.lock().unwrap().check_notes()
+}
+
+#[tauri::command]
+fn start_editing(
+    state: tauri::State<'_, PlayfieldState>,
+    window: Window,
+    include_clue_count: bool,
+    include_solution_count: bool,
+) -> Result<(), String> {
+    let mut playfield = state.playfield.lock().unwrap();
+    playfield.start_editing(Option::Some(&Request {
+        window,
+        include_clue_count,
+        include_solution_count,
+    }))
+}
+
+#[tauri::command]
+fn stop_editing(
+    state: tauri::State<'_, PlayfieldState>,
+    window: Window,
+    include_clue_count: bool,
+    include_solution_count: bool,
+) -> Result<u8, String> {
+    let mut playfield = state.playfield.lock().unwrap();
+    playfield.stop_editing(Option::Some(&Request {
+        window,
+        include_clue_count,
+        include_solution_count,
+    })).map(|state| state as u8)
+}
+
+#[tauri::command]
+fn lock_puzzle(
+    state: tauri::State<'_, PlayfieldState>,
+    window: Window,
+    include_clue_count: bool,
+    include_solution_count: bool,
+) -> Result<(), String> {
+    let mut playfield = state.playfield.lock().unwrap();
+    playfield.lock_puzzle(Option::Some(&Request {
+        window,
+        include_clue_count,
+        include_solution_count,
+    }))
+}
+
+#[tauri::command]
+fn editor_status(
+    state: tauri::State<'_, PlayfieldState>,
+) -> Result<EditorStatus, String> {
+    state.playfield.lock().unwrap().editor_status()
 }
 
 #[tauri::command]
@@ -167,6 +733,7 @@ fn trigger_update(
 #[tauri::command]
 fn increment_value(
     state:tauri::State<'_, PlayfieldState>,
+    app: AppHandle,
     window: Window,
     row:usize, col:usize,
     include_clue_count: bool,
@@ -174,8 +741,9 @@ fn increment_value(
 ) -> Result<(), String> {
     let mut playfield = state.playfield.lock().unwrap();
     let value = playfield.get_value(row, col);
+    let digits = playfield.get_variant().size.digits() as u8;
     playfield.set_value(
-        (value + 1) % 10, 
+        (value + 1) % (digits + 1), 
         row, 
         col, 
         Option::Some(&Request {
@@ -183,12 +751,13 @@ fn increment_value(
             include_clue_count,
             include_solution_count,
         })
-    ).map(|_| ())
+    ).map(|_| track_result(&app, &mut playfield))
 }
 
 #[tauri::command]
 fn set_value(
     state:tauri::State<'_, PlayfieldState>,
+    app: AppHandle,
     window: Window,
     row:usize, col:usize, value:u8,
     include_clue_count: bool,
@@ -204,19 +773,24 @@ fn set_value(
             include_clue_count,
             include_solution_count,
         })
-    ).map(|_| ())
+    ).map(|_| track_result(&app, &mut playfield))
 }
 
 #[tauri::command]
 fn reset(
     state:tauri::State<'_, PlayfieldState>,
+    app: AppHandle,
     window: Window,
     include_clue_count: bool,
     include_solution_count: bool,
     hard: bool, // if this is set to true, a completely new game is created
 ) -> Result<(), String> {
+    state.cancel_generation();
     let mut playfield = state.playfield.lock().unwrap();
     if hard {
+        track_abandoned(&app, &playfield);
+        // the fresh game isn't autosaved until it changes, so the replaced one mustn't be offered for recovery
+        clear_recovery(&app);
         *playfield = Game::new(0, Option::Some(&Request {
             window,
             include_clue_count,
@@ -232,9 +806,41 @@ fn reset(
     }
 }
 
+#[tauri::command]
+fn undo(
+    state:tauri::State<'_, PlayfieldState>,
+    window: Window,
+    include_clue_count: bool,
+    include_solution_count: bool,
+) -> Result<(), String> {
+    let mut playfield = state.playfield.lock().unwrap();
+    playfield.undo(Option::Some(&Request {
+        window,
+        include_clue_count,
+        include_solution_count,
+    })).map(|_| ())
+}
+
+#[tauri::command]
+fn redo(
+    state:tauri::State<'_, PlayfieldState>,
+    app: AppHandle,
+    window: Window,
+    include_clue_count: bool,
+    include_solution_count: bool,
+) -> Result<(), String> {
+    let mut playfield = state.playfield.lock().unwrap();
+    playfield.redo(Option::Some(&Request {
+        window,
+        include_clue_count,
+        include_solution_count,
+    })).map(|_| track_result(&app, &mut playfield))
+}
+
 #[tauri::command]
 fn solve(
     state:tauri::State<'_, PlayfieldState>,
+    app: AppHandle,
     window: Window,
     include_clue_count: bool,
     include_solution_count: bool,
@@ -244,26 +850,49 @@ fn solve(
         window,
         include_clue_count,
         include_solution_count,
-    })).map(|_| ())
+    })).map(|_| track_result(&app, &mut playfield))
 }
 
 fn main() {
     tauri::Builder::default()
         .manage(PlayfieldState {
             playfield: Mutex::new(Game::new(0, Option::None)),
+            generation: Mutex::new(Option::None),
+        })
+        .setup(|app| {
+            let handle = app.handle();
+            std::thread::spawn(move || loop {
+                std::thread::sleep(AUTOSAVE_INTERVAL);
+                let _ = autosave(&handle);
+            });
+            Ok(())
+        })
+        .on_window_event(|event| {
+            if let WindowEvent::CloseRequested { .. } = event.event() {
+                let _ = autosave(&event.window().app_handle());
+            }
         })
         .invoke_handler(tauri::generate_handler![
             increment_value,
-            generate,
+            generate, cancel_generation,
             set_value,
             reset,
             solve,
             serialize, deserialize,
+            list_saves, save_game, load_game, rename_save, duplicate_save, delete_save,
+            recovery_info, restore_recovery, discard_recovery,
+            get_statistics, get_game_records, reset_statistics,
+            import_puzzle, export_puzzle,
             increment_timer,
             trigger_update,
-            fix_current,
-            toggle_note,
-            hint, unhint,
+            fix_current, redundant_clues,
+            start_editing, stop_editing, lock_puzzle, editor_status,
+            set_variant, set_mistake_check,
+            toggle_note, toggle_corner_note, set_color,
+            fill_candidates, set_auto_eliminate, check_notes,
+            hint, unhint, explain_hint,
+            rate,
+            undo, redo,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -1,12 +1,21 @@
 use std::borrow::BorrowMut;
+use std::time::{SystemTime, UNIX_EPOCH};
 
-use crate::engine;
+use crate::engine::{self, Constraint, Size, Variant};
+use crate::logic::{self, Deduction, Explanation, Rating, Technique, Unit};
+use crate::savegame::{self, SaveMetadata};
+use crate::statistics::{GameRecord, Rules};
 use array2d::Array2D;
 use serde::{Serialize, Deserialize};
 use serde_json;
 
 use crate::Request;
 
+const COLOR_COUNT:u8 = 9;
+// oldest actions are dropped beyond this, the history is part of every save and autosave
+const MAX_HISTORY:usize = 200;
+const MISTAKE_HINT:&str = "No hint found, the grid contains a mistake";
+
 #[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq)]
 #[repr(u8)]
 pub enum CellState {
@@ -23,14 +32,35 @@ struct CellUpdateEvent {
     col: u8,
     value: u8,
     state: u8,
-    notes: [bool; 9],
+    notes: Vec<bool>,
+    corner_notes: Vec<bool>,
+    color: Option<u8>,
 }
 
 #[derive(serde::Serialize, Clone)]
 struct GameUpdateEvent {
     state: u8,
-    clue_count: Option<u8>,
+    clue_count: Option<u16>,
     solution_count: Option<u8>,
+    variant: Variant,
+}
+
+/// Live status of a puzzle while it is being set.
+#[derive(serde::Serialize, Clone, Debug, PartialEq)]
+pub struct EditorStatus {
+    /// Number of solutions, capped at 2.
+    pub solution_count: u8,
+    /// Clues that can be removed without losing uniqueness, empty unless the solution is unique.
+    pub redundant_clues: Vec<(usize, usize)>,
+}
+
+#[derive(serde::Serialize, Clone)]
+struct HintEvent {
+    technique: Technique,
+    units: Vec<Unit>,
+    cells: Vec<(usize, usize)>,
+    eliminations: Vec<(usize, usize, u8)>,
+    placement: Option<(usize, usize, u8)>,
 }
 
 impl TryFrom<u8> for CellState {
@@ -47,6 +77,18 @@ impl TryFrom<u8> for CellState {
     }
 }
 
+/// When wrong values are flagged as errors.
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq, Default)]
+pub enum MistakeCheck {
+    /// Values are checked against the solution once it is known, otherwise for conflicts with the rules.
+    #[default]
+    Solution,
+    /// Values are only checked for conflicts with the rules, even if the solution is known.
+    Conflicts,
+    /// Nothing is checked until the grid is full.
+    Deferred,
+}
+
 #[derive(Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
 pub enum GameState {
     Blank,
@@ -56,14 +98,21 @@ pub enum GameState {
     Editing,
 }
 
-#[derive(Serialize, Deserialize, Clone)]
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
 pub struct Cell {
     row:usize,
     col:usize,
     value: u8,
     cell_state: CellState,
     solution: Option<u8>,
-    notes: [bool; 9],
+    // center marks: the candidates of the cell
+    notes: Vec<bool>,
+    // corner marks: the cell is one of the few places of a digit in its region
+    #[serde(default)]
+    corner_notes: Vec<bool>,
+    // colour for marking chains, independent of the value
+    #[serde(default)]
+    color: Option<u8>,
 }
 
 impl Cell {
@@ -73,7 +122,9 @@ impl Cell {
             col: self.col as u8,
             value: self.value,
             state: self.cell_state as u8,
-            notes: self.notes,
+            notes: self.notes.clone(),
+            corner_notes: self.corner_notes.clone(),
+            color: self.color,
         };
         request.window.emit(&format!("updateCell-{}-{}", self.row, self.col), event).unwrap();
     }
@@ -82,6 +133,23 @@ impl Cell {
         self.cell_state = state;
     }
 
+    // checks a loaded cell against the number of digits of its grid
+    fn validate(&self, digits:usize) -> Result<(), String> {
+        if self.row >= digits || self.col >= digits {
+            return Err(format!("cell ({}, {}) is outside of the grid", self.row, self.col));
+        }
+        if self.value as usize > digits || self.solution.is_some_and(|solution| solution == 0 || solution as usize > digits) {
+            return Err(format!("cell ({}, {}) holds a digit out of range", self.row, self.col));
+        }
+        if self.notes.len() != digits || self.corner_notes.len() != digits {
+            return Err(format!("cell ({}, {}) doesn't have {} notes", self.row, self.col, digits));
+        }
+        if self.color.is_some_and(|color| !(1..=COLOR_COUNT).contains(&color)) {
+            return Err(format!("cell ({}, {}) has an unknown colour", self.row, self.col));
+        }
+        Ok(())
+    }
+
     fn set_value(&mut self, value:u8) -> Result<(bool, u8), String> {   
         let changed = value != self.value;
         match self.cell_state {
@@ -101,6 +169,7 @@ impl Cell {
                 if value > 0 {
                     self.set_state(CellState::Set);
                     self.notes.iter_mut().for_each(|note| *note = false);
+                    self.corner_notes.iter_mut().for_each(|note| *note = false);
                 }
             }
             CellState::Set => {
@@ -139,6 +208,34 @@ impl Cell {
             CellState::Error | CellState::Fix | CellState::Set | CellState::Hint => Err("Notes are only allowed on blank cells".into())
         }
     }
+
+    fn toggle_corner_note(&mut self, value:usize) -> Result<(), String> {
+        if value == 0 {
+            return Err("value must be greater than 0".into())
+        }
+        match self.cell_state {
+            CellState::Blank => {
+                self.corner_notes[value - 1] = !self.corner_notes[value - 1];
+                Ok(())
+            },
+            CellState::Error | CellState::Fix | CellState::Set | CellState::Hint => Err("Notes are only allowed on blank cells".into())
+        }
+    }
+}
+
+// A single recorded mutation: all changed cells before and after, plus the game state transition
+#[derive(Serialize, Deserialize, Clone)]
+struct Action {
+    before: Vec<Cell>,
+    after: Vec<Cell>,
+    state_before: GameState,
+    state_after: GameState,
+}
+
+#[derive(Serialize, Deserialize, Clone, Default)]
+struct History {
+    undo: Vec<Action>,
+    redo: Vec<Action>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -148,14 +245,142 @@ pub struct Game {
     difficulty: u8,
     timer_seconds: u32,
     seed: u64,
+    #[serde(default)]
+    rating: Option<Rating>,
+    #[serde(default)]
+    history: History,
+    #[serde(default)]
+    variant: Variant,
+    // placing a digit removes the notes it rules out from the other cells
+    #[serde(default)]
+    auto_eliminate: bool,
+    #[serde(default)]
+    mistake_check: MistakeCheck,
+    // counted for the statistics, reset when a puzzle starts
+    #[serde(default)]
+    hints: u32,
+    #[serde(default)]
+    mistakes: u32,
+    #[serde(default)]
+    solver_used: bool,
+    // the result was added to the statistics, see `mark_recorded`
+    #[serde(default)]
+    recorded: bool,
+    // rules of the variant, rebuilt whenever the variant changes
+    #[serde(skip)]
+    constraints: Vec<Box<dyn Constraint>>,
+    // changed since the last autosave, the timer alone doesn't count
+    #[serde(skip)]
+    unsaved: bool,
 }
 
 impl Game {
-    pub fn from_json(string: &str, request:Option<&Request>) -> Game {
-        let mut p:Game = serde_json::from_str(string).unwrap();
+    /// Loads a game saved with [`Game::to_json`], older save formats are migrated.
+    pub fn from_json(string: &str, request:Option<&Request>) -> Result<Game, String> {
+        let save = savegame::decode(string)?;
+        let mut p:Game = serde_json::from_value(save.game).map_err(|e| match save.format_version > savegame::FORMAT_VERSION {
+            true => format!("Save file was written by a newer version ({}): {}", save.app_version, e),
+            false => format!("Invalid save file: {}", e),
+        })?;
+        p.validate().map_err(|e| format!("Invalid save file: {}", e))?;
+        p.constraints = p.variant.constraints();
+        p.unsaved = true;
 
         request.inspect(|r| p.emit_update_event(r));
-        p
+        Ok(p)
+    }
+
+    // checks that the cells of a loaded game, also those in the history, fit the grid of its variant
+    fn validate(&self) -> Result<(), String> {
+        self.variant.validate()?;
+        let digits = self.variant.size.digits();
+        if self.cells.len() != digits || self.cells.iter().any(|row| row.len() != digits) {
+            return Err(format!("grid must have {} rows of {} cells", digits, digits));
+        }
+        for (row, cells) in self.cells.iter().enumerate() {
+            for (col, cell) in cells.iter().enumerate() {
+                if cell.row != row || cell.col != col {
+                    return Err(format!("cell ({}, {}) is stored at ({}, {})", cell.row, cell.col, row, col));
+                }
+                cell.validate(digits)?;
+            }
+        }
+        self.history.undo.iter().chain(self.history.redo.iter())
+            .flat_map(|action| action.before.iter().chain(action.after.iter()))
+            .try_for_each(|cell| cell.validate(digits))
+    }
+
+    /// Loads a puzzle in the 81-character format, optionally followed by its solution.
+    /// Whitespace is ignored, so both grids may be given in one line each or spread over nine lines.
+    /// All given values are fixed. Without a solution, the puzzle must have a unique solution.
+    pub fn from_puzzle_string(string: &str, request:Option<&Request>) -> Result<Game, String> {
+        let grid:Vec<char> = string.chars().filter(|c| !c.is_whitespace()).collect();
+        match grid.len() {
+            0 => return Err("Puzzle is empty".into()),
+            81 | 162 => {},
+            _ => return Err("Expected 81 characters for the puzzle and optionally 81 for its solution".into()),
+        };
+        let (puzzle, solution) = grid.split_at(81);
+        let clues = engine::parse_grid(&puzzle.iter().collect::<String>())?;
+        let solution = match solution.is_empty() {
+            false => {
+                let solution = engine::parse_grid(&solution.iter().collect::<String>())?;
+                let is_consistent = clues.elements_row_major_iter()
+                    .zip(solution.elements_row_major_iter())
+                    .all(|(clue, value)| *value > 0 && (*clue == 0 || clue == value));
+                if !is_consistent {
+                    return Err("Solution does not match the puzzle".into());
+                }
+                solution
+            },
+            true => {
+                if engine::count_solutions(&Variant::default(), &clues, 2) != 1 {
+                    return Err("Must have a unique solution to start solving".into());
+                }
+                engine::solve(&Variant::default(), &clues, Option::None)?
+            }
+        };
+
+        let difficulty = clues.elements_row_major_iter().filter(|value| **value == 0).count() as u8;
+        let mut p = Game::new(difficulty, Option::None);
+        p.cells.iter_mut().flatten().for_each(|cell| {
+            cell.value = clues[(cell.row, cell.col)];
+            if cell.value > 0 {
+                cell.set_state(CellState::Fix);
+            }
+            cell.solution = Option::Some(solution[(cell.row, cell.col)]);
+        });
+        p.state = GameState::Running;
+        p.rating = logic::rate(&clues).ok();
+        p.unsaved = true;
+
+        request.inspect(|r| p.emit_update_event(r));
+        Ok(p)
+    }
+
+    /// Exports the givens in the 81-character format, optionally followed by a line with the solution.
+    /// If no values are fixed yet, all current values are exported.
+    pub fn to_puzzle_string(&self, include_solution:bool) -> Result<String, String> {
+        if !self.variant.is_classic() {
+            return Err("Only classic grids can be exported".into());
+        }
+
+        let has_givens = self.cells.iter().flatten().any(|cell| cell.cell_state == CellState::Fix);
+        let mut givens = Array2D::filled_with(0, 9, 9);
+        self.cells.iter().flatten()
+            .filter(|cell| !has_givens || cell.cell_state == CellState::Fix)
+            .for_each(|cell| givens[(cell.row, cell.col)] = cell.value);
+
+        let mut string = engine::format_grid(&givens);
+        if include_solution {
+            let solution = match self.cells.iter().flatten().map(|cell| cell.solution).collect::<Option<Vec<u8>>>() {
+                Some(values) => Array2D::from_row_major(&values, 9, 9).map_err(|_| "Invalid solution".to_string())?,
+                None => engine::solve(&self.variant, &givens, Option::None)?,
+            };
+            string.push('\n');
+            string.push_str(&engine::format_grid(&solution));
+        }
+        Ok(string)
     }
 
     pub fn get_state(&self) -> GameState {
@@ -163,84 +388,329 @@ impl Game {
     }
 
     pub fn hint(&mut self, request:Option<&Request>) -> Result<(), String> {
+        self.explain_hint(true, request).map(|_| ())
+    }
+
+    /// Explains the next logical placement and emits it as `showHint` event.
+    /// Without `reveal`, only a nudge (technique and units) is emitted and the grid stays untouched.
+    pub fn explain_hint(&mut self, reveal:bool, request:Option<&Request>) -> Result<Explanation, String> {
+        self.record(|game| game.explain_next(reveal, request))
+    }
+
+    fn explain_next(&mut self, reveal:bool, request:Option<&Request>) -> Result<Explanation, String> {
         match self.state {
-            GameState::Error | GameState::Solved => {
+            GameState::Editing | GameState::Error | GameState::Solved => {
                 return Err("Hints not possible in current state".into());
             },
-            GameState::Editing | GameState::Blank | GameState::Running => {}
+            GameState::Blank | GameState::Running => {}
         };
+        let explanation = match self.variant.is_classic() {
+            true => self.logical_explanation()?,
+            false => self.solution_explanation()?,
+        };
+        let (row, col, digit) = explanation.placement().ok_or("No hint found")?;
 
-        engine::hint(&self.get_values()).map(|(row, col)| {
-            let cell = &mut self.cells[row][col];
-            cell.solution.inspect(|solution| cell.value = *solution);
-            cell.set_state(CellState::Hint);
-            request.inspect(|r| cell.emit_update_event(r));
+        let mut units:Vec<Unit> = Vec::new();
+        explanation.deductions.iter().flat_map(|d| d.units.iter()).for_each(|unit| {
+            if !units.contains(unit) {
+                units.push(*unit);
+            }
+        });
+        let event = match reveal {
+            true => {
+                let cell = &mut self.cells[row][col];
+                cell.value = digit;
+                cell.set_state(CellState::Hint);
+                request.inspect(|r| cell.emit_update_event(r));
+
+                HintEvent {
+                    technique: explanation.technique(),
+                    units,
+                    cells: explanation.deductions.iter().flat_map(|d| d.cells.iter().copied()).collect(),
+                    eliminations: explanation.deductions.iter().flat_map(|d| d.eliminations.iter().copied()).collect(),
+                    placement: Option::Some((row, col, digit)),
+                }
+            },
+            false => HintEvent {
+                technique: explanation.technique(),
+                units,
+                cells: vec![],
+                eliminations: vec![],
+                placement: Option::None,
+            }
+        };
+        request.inspect(|r| r.window.emit("showHint", event).unwrap());
+        self.hints += 1;
+
+        Ok(explanation)
+    }
+
+    // Entries are taken as they are, so the hint lands on a blank cell and doesn't tell which entries are wrong.
+    // Deductions that contradict the solution were built on a wrong entry, which is reported instead.
+    fn logical_explanation(&self) -> Result<Explanation, String> {
+        let explanation = logic::explain(&self.get_values()).map_err(|_| MISTAKE_HINT.to_string())?;
+        let is_misled = explanation.deductions.iter().any(|d| {
+            d.placements.iter().any(|(r,c,digit)| self.cells[*r][*c].solution.is_some_and(|solution| solution != *digit))
+                || d.eliminations.iter().any(|(r,c,digit)| self.cells[*r][*c].solution == Option::Some(*digit))
+        });
+        match is_misled {
+            true => Err(MISTAKE_HINT.into()),
+            false => Ok(explanation),
+        }
+    }
+
+    // The logical solver only knows classic grids, other variants reveal the solution of the most constrained blank cell
+    fn solution_explanation(&self) -> Result<Explanation, String> {
+        if self.cells.iter().flatten().any(|cell| cell.value > 0 && cell.solution.is_some_and(|solution| solution != cell.value)) {
+            return Err(MISTAKE_HINT.into());
+        }
+        let candidates = engine::candidates(&self.variant, &self.get_values()).map_err(|_| MISTAKE_HINT.to_string())?;
+        let (row, col, digit) = self.cells.iter().flatten()
+            .filter(|cell| cell.value == 0)
+            .filter_map(|cell| cell.solution.map(|solution| (cell.row, cell.col, solution)))
+            .min_by_key(|(r,c,_)| candidates[(*r,*c)].count_ones())
+            .ok_or("No hint found")?;
+        Ok(Explanation {
+            deductions: vec![Deduction {
+                technique: Technique::Backtracking,
+                cells: vec![(row, col)],
+                units: vec![],
+                placements: vec![(row, col, digit)],
+                eliminations: vec![],
+            }],
         })
     }
 
     pub fn unhint(&mut self, request:Option<&Request>) {
-        self.cells.iter_mut().flatten().for_each(|cell| {
-            match cell.cell_state {
-                CellState::Blank | CellState::Error | CellState::Fix | CellState::Set => {},
-                CellState::Hint => {
-                    let _ = cell.set_value(0);
-                    request.inspect(|r| cell.emit_update_event(r));
-                },
-            };
+        let _ = self.record(|game| {
+            game.cells.iter_mut().flatten().for_each(|cell| {
+                match cell.cell_state {
+                    CellState::Blank | CellState::Error | CellState::Fix | CellState::Set => {},
+                    CellState::Hint => {
+                        let _ = cell.set_value(0);
+                        request.inspect(|r| cell.emit_update_event(r));
+                    },
+                };
+            });
+            Ok(())
         });
     }
 
     pub fn new(difficulty:u8, request:Option<&Request>) -> Game {
+        Game::with_size(Size::default(), difficulty, request)
+    }
+
+    pub fn with_size(size:Size, difficulty:u8, request:Option<&Request>) -> Game {
+        let variant = Variant::sized(size);
+        let mut p = Game { 
+            cells: Game::new_cells(size),
+            state: GameState::Blank,
+            difficulty,
+            timer_seconds: 0,
+            seed: 42,
+            rating: Option::None,
+            history: History::default(),
+            auto_eliminate: false,
+            mistake_check: MistakeCheck::default(),
+            hints: 0,
+            mistakes: 0,
+            solver_used: false,
+            recorded: false,
+            constraints: variant.constraints(),
+            variant,
+            unsaved: false,
+        };
+        if let Some(r) = request {
+            p.emit_update_event(r);
+        }
+
+        p
+    }
+
+    fn new_cells(size:Size) -> Vec<Vec<Cell>> {
         let mut cells:Vec<Vec<Cell>> = Vec::new();
         
-        for row in 0..9 {
+        for row in 0..size.digits() {
             let mut row_cells: Vec<Cell> = Vec::new();
-            for col in 0..9 {
+            for col in 0..size.digits() {
                 row_cells.push(Cell {
                     row,
                     col,
                     value: 0,
                     cell_state: CellState::Blank,
                     solution: Option::None,
-                    notes: [false; 9],
+                    notes: vec![false; size.digits()],
+                    corner_notes: vec![false; size.digits()],
+                    color: Option::None,
                 });
             }
             cells.push(row_cells);
         }
-        let mut p = Game { 
-            cells,
-            state: GameState::Blank,
-            difficulty,
-            timer_seconds: 0,
-            seed: 42,
-        };
-        if let Some(r) = request {
-            p.emit_update_event(r);
-        }
+        cells
+    }
 
-        p
+    pub fn get_variant(&self) -> &Variant {
+        &self.variant
     }
 
     pub fn toggle_note(&mut self, row:usize, col:usize, value:usize, request:Option<&Request>) -> Result<(), String> {
-        if value < 1 || value > 9 {
-            return Err("Note value must be between 1 and 9".into());
+        let digits = self.variant.size.digits();
+        if row >= digits || col >= digits {
+            return Err(format!("Cell must be within the {}×{} grid", digits, digits));
+        }
+        if value < 1 || value > digits {
+            return Err(format!("Note value must be between 1 and {}", digits));
         }
         
-        let result = self.cells[row][col].toggle_note(value);
-        request.inspect(|r| self.cells[row][col].emit_update_event(*r));
+        self.record(|game| {
+            let result = game.cells[row][col].toggle_note(value);
+            request.inspect(|r| game.cells[row][col].emit_update_event(r));
 
-        result
+            result
+        })
+    }
+
+    pub fn toggle_corner_note(&mut self, row:usize, col:usize, value:usize, request:Option<&Request>) -> Result<(), String> {
+        let digits = self.variant.size.digits();
+        if row >= digits || col >= digits {
+            return Err(format!("Cell must be within the {}×{} grid", digits, digits));
+        }
+        if value < 1 || value > digits {
+            return Err(format!("Note value must be between 1 and {}", digits));
+        }
+        
+        self.record(|game| {
+            let result = game.cells[row][col].toggle_corner_note(value);
+            request.inspect(|r| game.cells[row][col].emit_update_event(r));
+
+            result
+        })
     }
 
+    /// Colours a cell, or removes its colour with `Option::None`.
+    pub fn set_color(&mut self, row:usize, col:usize, color:Option<u8>, request:Option<&Request>) -> Result<(), String> {
+        let digits = self.variant.size.digits();
+        if row >= digits || col >= digits {
+            return Err(format!("Cell must be within the {}×{} grid", digits, digits));
+        }
+        if color.is_some_and(|color| !(1..=COLOR_COUNT).contains(&color)) {
+            return Err(format!("Colour must be between 1 and {}", COLOR_COUNT));
+        }
+
+        self.record(|game| {
+            game.cells[row][col].color = color;
+            request.inspect(|r| game.cells[row][col].emit_update_event(r));
+            Ok(())
+        })
+    }
+
+    /// Fills the notes of all blank cells with their candidates under the rules of the variant.
+    /// With `keep_notes`, cells that already have notes are left untouched to keep the user's eliminations.
+    /// Values in error are ignored, as are entries that conflict with another value but aren't flagged yet.
+    pub fn fill_candidates(&mut self, keep_notes:bool, request:Option<&Request>) -> Result<(), String> {
+        let values = self.get_values();
+        let mut consistent = values.clone();
+        self.cells.iter().flatten()
+            .filter(|cell| match cell.cell_state {
+                CellState::Error => true,
+                CellState::Set => self.constraints.iter().any(|constraint| constraint.conflicts((cell.row, cell.col), &values)),
+                CellState::Blank | CellState::Fix | CellState::Hint => false,
+            })
+            .for_each(|cell| consistent[(cell.row, cell.col)] = 0);
+        let candidates = engine::candidates(&self.variant, &consistent)?;
+
+        self.record(|game| {
+            for cell in game.cells.iter_mut().flatten() {
+                if cell.cell_state != CellState::Blank || (keep_notes && cell.notes.contains(&true)) {
+                    continue;
+                }
+                let notes:Vec<bool> = (0..cell.notes.len()).map(|d| candidates[(cell.row, cell.col)] >> d & 1 == 1).collect();
+                if notes != cell.notes {
+                    cell.notes = notes;
+                    request.inspect(|r| cell.emit_update_event(r));
+                }
+            }
+            Ok(())
+        })
+    }
+
+    /// Saves the game in an envelope of the current save format, see [`savegame::encode`].
     pub fn to_json(&self) -> Result<String, serde_json::Error> {
-        serde_json::to_string(&self)
+        savegame::encode(self, self.metadata())
+    }
+
+    /// Returns whether the game changed since it was last marked as saved, see [`Game::mark_saved`].
+    pub fn has_unsaved_changes(&self) -> bool {
+        self.unsaved
+    }
+
+    pub fn mark_saved(&mut self) {
+        self.unsaved = false;
+    }
+
+    /// Returns the result of the puzzle once it is solved, until it is marked as recorded.
+    /// Grids without givens aren't puzzles and have no result.
+    pub fn pending_result(&self) -> Option<GameRecord> {
+        if self.state != GameState::Solved || !self.is_puzzle() || self.recorded {
+            return Option::None;
+        }
+        Option::Some(self.result(false))
+    }
+
+    /// Returns the result of a puzzle that the player made moves in but didn't solve, recorded when the game is replaced by another one.
+    pub fn abandoned_result(&self) -> Option<GameRecord> {
+        let moved = !self.history.undo.is_empty() || !self.history.redo.is_empty();
+        match self.state {
+            GameState::Running | GameState::Error if moved && self.is_puzzle() && !self.recorded => Option::Some(self.result(true)),
+            GameState::Running | GameState::Error | GameState::Blank | GameState::Editing | GameState::Solved => Option::None,
+        }
     }
 
-    pub fn get_clue_count(&self) -> u8 {
+    fn is_puzzle(&self) -> bool {
+        self.cells.iter().flatten().any(|cell| cell.cell_state == CellState::Fix)
+    }
+
+    fn result(&self, abandoned:bool) -> GameRecord {
+        GameRecord {
+            difficulty: self.difficulty,
+            rating: self.rating,
+            rules: Rules::from(&self.variant),
+            seed: self.seed,
+            timer_seconds: self.timer_seconds,
+            hints: self.hints,
+            mistakes: self.mistakes,
+            solver_used: self.solver_used,
+            abandoned,
+            finished_at: SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0),
+        }
+    }
+
+    /// Marks the result as added to the statistics, call it only once the statistics are saved.
+    pub fn mark_recorded(&mut self) {
+        self.recorded = true;
+        self.unsaved = true;
+    }
+
+    /// Summarizes the game for the savegame library.
+    pub fn metadata(&self) -> SaveMetadata {
+        let open = self.cells.iter().flatten().filter(|cell| cell.cell_state != CellState::Fix);
+        let (filled, total) = open.fold((0, 0), |(filled, total), cell| (filled + (cell.value > 0) as usize, total + 1));
+        SaveMetadata {
+            difficulty: self.difficulty,
+            progress: match total {
+                0 => 100,
+                _ => (filled * 100 / total) as u8,
+            },
+            timer_seconds: self.timer_seconds,
+            thumbnail: engine::format_grid(&self.get_values()),
+        }
+    }
+
+    pub fn get_clue_count(&self) -> u16 {
         self.cells.iter().flatten().filter(|s| match s.cell_state {
             CellState::Fix | CellState::Error | CellState::Set | CellState::Hint => true,
             CellState::Blank => false,            
-        }).count() as u8
+        }).count() as u16
     }
 
     pub fn increment_timer(&mut self) -> Result<u32, String> {
@@ -253,29 +723,132 @@ impl Game {
     }
 
     pub fn reset(&mut self, request:Option<&Request>) -> Result<GameState, String> {
-        self.cells.iter_mut().flatten().for_each(|cell_ref| {
-            let _ = cell_ref.set_value(0).inspect(|(changed, _)| {
-                if *changed {
-                    request.inspect(|r| cell_ref.emit_update_event(r));
-                }
+        self.record(|game| {
+            game.cells.iter_mut().flatten().for_each(|cell_ref| {
+                let _ = cell_ref.set_value(0).inspect(|(changed, _)| {
+                    if *changed {
+                        request.inspect(|r| cell_ref.emit_update_event(r));
+                    }
+                });
             });
-        });
-        Ok(self.state)
+            Ok(game.state)
+        })
     }
 
     pub fn set_value(&mut self, value:u8, row:usize, col:usize, request:Option<&Request>) -> Result<u8, String> {
-        match self.state {
-            GameState::Blank => self.state = GameState::Running,
-            GameState::Solved => return Err("Already solved".into()),
-            GameState::Editing | GameState::Error | GameState::Running => {}        
-        };
-        
-        self.cells[row][col].set_value(value).map(|(changed, new_value)| {
+        let digits = self.variant.size.digits();
+        if value as usize > digits {
+            return Err(format!("Value must be between 0 and {}", digits));
+        }
+
+        self.record(|game| {
+            match game.state {
+                GameState::Blank => game.state = GameState::Running,
+                GameState::Solved => return Err("Already solved".into()),
+                GameState::Editing | GameState::Error | GameState::Running => {}        
+            };
+            
+            game.cells[row][col].set_value(value).map(|(changed, new_value)| {
+                if changed {
+                    if game.auto_eliminate {
+                        game.eliminate_notes(row, col, request);
+                    }
+                    game.update_states(request);
+                    if game.cells[row][col].cell_state == CellState::Error {
+                        game.mistakes += 1;
+                    }
+                }
+                new_value
+            })
+        })
+    }
+
+    /// Enables or disables the removal of notes ruled out by a placed digit.
+    pub fn set_auto_eliminate(&mut self, enabled:bool) {
+        self.auto_eliminate = enabled;
+        self.unsaved = true;
+    }
+
+    // Removes the notes of blank cells that conflict with the value of the given cell under the rules of the variant,
+    // the cells that lost notes are updated
+    fn eliminate_notes(&mut self, row:usize, col:usize, request:Option<&Request>) {
+        let digits = self.variant.size.digits();
+        let mut values = Array2D::filled_with(0, digits, digits);
+        values[(row, col)] = self.cells[row][col].value;
+        if values[(row, col)] == 0 {
+            return;
+        }
+
+        for cell in self.cells.iter_mut().flatten().filter(|cell| cell.cell_state == CellState::Blank) {
+            let mut changed = false;
+            for mov_zero_based in 0..digits {
+                if !cell.notes[mov_zero_based] {
+                    continue;
+                }
+                values[(cell.row, cell.col)] = (mov_zero_based + 1) as u8;
+                if self.constraints.iter().any(|constraint| constraint.conflicts((cell.row, cell.col), &values)) {
+                    cell.notes[mov_zero_based] = false;
+                    changed = true;
+                }
+            }
+            values[(cell.row, cell.col)] = 0;
             if changed {
-                self.update_states(request)
+                request.inspect(|r| cell.emit_update_event(r));
             }
-            new_value
-        })
+        }
+    }
+
+    /// Reverts the last recorded mutation.
+    pub fn undo(&mut self, request:Option<&Request>) -> Result<GameState, String> {
+        let action = self.history.undo.pop().ok_or("Nothing to undo")?;
+        self.restore(&action.before, action.state_before, request);
+        self.history.redo.push(action);
+        Ok(self.state)
+    }
+
+    /// Reapplies the last reverted mutation.
+    pub fn redo(&mut self, request:Option<&Request>) -> Result<GameState, String> {
+        let action = self.history.redo.pop().ok_or("Nothing to redo")?;
+        self.restore(&action.after, action.state_after, request);
+        self.history.undo.push(action);
+        Ok(self.state)
+    }
+
+    fn restore(&mut self, cells:&[Cell], state:GameState, request:Option<&Request>) {
+        for cell in cells.iter() {
+            self.cells[cell.row][cell.col] = cell.clone();
+            request.inspect(|r| cell.emit_update_event(r));
+        }
+        self.state = state;
+        self.unsaved = true;
+        request.inspect(|r| self.emit_update_game_event(r));
+    }
+
+    // Runs a mutation and records all changed cells and the state transition in the history
+    fn record<T>(&mut self, mutation:impl FnOnce(&mut Game) -> Result<T, String>) -> Result<T, String> {
+        let cells_before:Vec<Cell> = self.cells.iter().flatten().cloned().collect();
+        let state_before = self.state;
+
+        let result = mutation(self);
+
+        let (before, after):(Vec<Cell>, Vec<Cell>) = cells_before.into_iter()
+            .zip(self.cells.iter().flatten())
+            .filter(|(before, after)| before != *after)
+            .map(|(before, after)| (before, after.clone()))
+            .unzip();
+        if !before.is_empty() || state_before != self.state {
+            self.history.undo.push(Action {
+                before,
+                after,
+                state_before,
+                state_after: self.state,
+            });
+            let excess = self.history.undo.len().saturating_sub(MAX_HISTORY);
+            self.history.undo.drain(..excess);
+            self.history.redo.clear();
+            self.unsaved = true;
+        }
+        result
     }
 
     pub fn get_value(&self, row:usize, col:usize) -> u8 {
@@ -283,9 +856,10 @@ impl Game {
     }
 
     pub fn get_values(&self) -> Array2D<u8> {
-        let mut a = Array2D::filled_with(0, 9, 9);
-        for row in 0..9 {
-            for col in 0..9 {
+        let digits = self.variant.size.digits();
+        let mut a = Array2D::filled_with(0, digits, digits);
+        for row in 0..digits {
+            for col in 0..digits {
                 a[(row, col)] = self.cells[row][col].value;
             }
         }
@@ -294,42 +868,186 @@ impl Game {
 
     fn is_error(&self, row:usize, col:usize) -> bool {
         let cell = &self.cells[row][col];
-        match cell.solution.as_ref() {
-            None => {
-                if cell.value == 0 {
+        if cell.value == 0 {
+            return false;
+        }
+
+        // while editing, there's no solution to compare with yet
+        let check_solution = self.state != GameState::Editing && match self.mistake_check {
+            MistakeCheck::Solution => true,
+            MistakeCheck::Conflicts => false,
+            MistakeCheck::Deferred => {
+                if self.cells.iter().flatten().any(|cell| cell.value == 0) {
                     return false;
                 }
+                true
+            },
+        };
+        match (cell.solution, check_solution) {
+            (Some(solution), true) => cell.value != solution,
+            (Some(_), false) | (None, _) => {
+                let values = self.get_values();
+                self.constraints.iter().any(|constraint| constraint.conflicts((row, col), &values))
+            },
+        }
+    }
 
-                for i in 0..9 {
-                    if i != col && cell.value == self.get_value(row, i) {
-                        return true;
-                    }
-                    if i != row && cell.value == self.get_value(i, col) {
-                        return true;
-                    }
-                    let r = i / 3 + 3*(row/3);
-                    let c = i % 3 + 3*(col/3);
-                    if !(r == row && c == col) && cell.value == self.get_value(r, c) {
-                        return true;
-                    }
+    /// Changes when wrong values are flagged as errors and updates the cell states accordingly.
+    pub fn set_mistake_check(&mut self, mistake_check:MistakeCheck, request:Option<&Request>) {
+        self.mistake_check = mistake_check;
+        self.update_states(request);
+    }
+
+    pub fn rate(&self) -> Result<Rating, String> {
+        if !self.variant.is_classic() {
+            return Err("Only classic grids can be rated".into());
+        }
+        logic::rate(&self.get_values())
+    }
+
+    /// Changes the rules of the game, e.g. the cages of a Killer Sudoku.
+    /// Rules can't be changed once solving has started.
+    pub fn set_variant(&mut self, variant:Variant, request:Option<&Request>) -> Result<(), String> {
+        if self.cells.iter().flatten().any(|cell| cell.solution.is_some()) {
+            return Err("Rules can't be changed while solving".into());
+        }
+        variant.validate()?;
+
+        self.apply_variant(variant);
+        self.update_states(request);
+        Ok(())
+    }
+
+    // Switches to the rules of the variant, the cells are replaced if the size changes
+    fn apply_variant(&mut self, variant:Variant) {
+        if variant.size != self.variant.size {
+            self.cells = Game::new_cells(variant.size);
+            self.history = History::default();
+        }
+        self.constraints = variant.constraints();
+        self.variant = variant;
+        self.unsaved = true;
+    }
+
+    /// Returns the blank cells whose notes miss their true digit, without revealing it.
+    /// The digits are taken from the known solution, or from the unique solution of the current values.
+    /// Cells without notes are not checked.
+    pub fn check_notes(&self) -> Result<Vec<(usize, usize)>, String> {
+        let digits = self.variant.size.digits();
+        let solution = match self.cells.iter().flatten().map(|cell| cell.solution).collect::<Option<Vec<u8>>>() {
+            Some(values) => Array2D::from_row_major(&values, digits, digits).map_err(|_| "Invalid solution".to_string())?,
+            None => {
+                let mut values = self.get_values();
+                self.cells.iter().flatten()
+                    .filter(|cell| cell.cell_state == CellState::Error)
+                    .for_each(|cell| values[(cell.row, cell.col)] = 0);
+                if engine::count_solutions(&self.variant, &values, 2) != 1 {
+                    return Err("Notes can only be checked against a unique solution".into());
                 }
-                return false;
-            },
-            Some(solution) => cell.value > 0 && cell.value != *solution
+                engine::solve(&self.variant, &values, Option::None)?
+            }
+        };
+
+        Ok(self.cells.iter().flatten()
+            .filter(|cell| cell.cell_state == CellState::Blank && cell.notes.contains(&true))
+            .filter(|cell| !cell.notes[(solution[(cell.row, cell.col)] - 1) as usize])
+            .map(|cell| (cell.row, cell.col))
+            .collect())
+    }
+
+    /// Returns the clues that can be removed without losing uniqueness.
+    /// While editing, all values are clues, otherwise only the fixed cells.
+    pub fn redundant_clues(&self) -> Result<Vec<(usize, usize)>, String> {
+        let mut clues = self.get_values();
+        if self.state != GameState::Editing {
+            self.cells.iter().flatten()
+                .filter(|cell| cell.cell_state != CellState::Fix)
+                .for_each(|cell| clues[(cell.row, cell.col)] = 0);
         }
+        engine::redundant_clues(&self.variant, &clues)
     }
 
-    pub fn generate(&mut self, difficulty:u8, seed:u64, request:Option<&Request>, fix_result:bool) -> Result<GameState, String> {
-        self.difficulty = difficulty;
-        self.seed = seed;
+    /// Switches to setter mode, where all values are clues of the puzzle being set.
+    /// The givens of the current puzzle become editable; values entered while solving are dropped.
+    /// Without givens, all values are kept, e.g. to continue a hand-entered grid.
+    pub fn start_editing(&mut self, request:Option<&Request>) -> Result<(), String> {
+        if self.state == GameState::Editing {
+            return Err("Already editing".into());
+        }
 
-        let result = engine::generate(&self.get_values(), seed, difficulty);
-        if result.is_err() {
-            return Err(result.unwrap_err());
+        let has_givens = self.cells.iter().flatten().any(|cell| cell.cell_state == CellState::Fix);
+        self.cells.iter_mut().flatten().for_each(|cell| {
+            let keep = match cell.cell_state {
+                CellState::Fix => true,
+                CellState::Blank | CellState::Set | CellState::Error | CellState::Hint => !has_givens,
+            };
+            if !keep {
+                cell.value = 0;
+            }
+            cell.set_state(if cell.value > 0 { CellState::Set } else { CellState::Blank });
+            cell.solution = Option::None;
+        });
+
+        self.state = GameState::Editing;
+        self.history = History::default();
+        self.update_states(request);
+        Ok(())
+    }
+
+    /// Leaves setter mode without locking, the values stay editable.
+    pub fn stop_editing(&mut self, request:Option<&Request>) -> Result<GameState, String> {
+        if self.state != GameState::Editing {
+            return Err("Not editing".into());
+        }
+
+        self.state = GameState::Blank;
+        self.history = History::default();
+        self.update_states(request);
+        Ok(self.state)
+    }
+
+    /// Leaves setter mode and turns the values into the givens of a new puzzle.
+    /// The puzzle must have a unique solution, otherwise the game stays in setter mode.
+    pub fn lock_puzzle(&mut self, request:Option<&Request>) -> Result<(), String> {
+        let status = self.editor_status()?;
+        if status.solution_count != 1 {
+            return Err("Must have a unique solution to lock the puzzle".into());
         }
-        let (clues, solution) = result.unwrap();
 
+        self.start_solving()?;
+        self.timer_seconds = 0;
+        self.rating = Option::None;
+        request.inspect(|r| self.emit_update_event(r));
+        Ok(())
+    }
+
+    /// Returns whether the puzzle being set is unique and which of its clues are redundant.
+    pub fn editor_status(&self) -> Result<EditorStatus, String> {
+        if self.state != GameState::Editing {
+            return Err("Not editing".into());
+        }
+
+        let has_conflicts = self.cells.iter().flatten().any(|cell| cell.cell_state == CellState::Error);
+        let solution_count = match has_conflicts {
+            true => 0,
+            false => engine::count_solutions(&self.variant, &self.get_values(), 2),
+        };
+        let redundant_clues = match solution_count {
+            1 => self.redundant_clues()?,
+            _ => vec![],
+        };
+        Ok(EditorStatus { solution_count, redundant_clues })
+    }
 
+    /// Installs a generated `(clues, solution, rating)` tuple along with the rules it was generated for.
+    /// Used to install grids generated off the game, see [`engine::generate_monitored`].
+    pub fn install_generated(&mut self, generated:(Array2D<u8>, Array2D<u8>, Option<Rating>), variant:Variant, difficulty:u8, seed:u64, request:Option<&Request>, fix_result:bool) -> GameState {
+        let (clues, solution, rating) = generated;
+        self.apply_variant(variant);
+        self.difficulty = difficulty;
+        self.seed = seed;
+        self.rating = rating;
+        self.history = History::default();
         self.cells.iter_mut().flatten().for_each(|cell| {
             let _ = cell.set_value(clues[(cell.row.into(), cell.col.into())]);
             if fix_result {
@@ -342,7 +1060,7 @@ impl Game {
         }
         
         request.inspect(|r| self.emit_update_event(r));
-        Ok(self.state)
+        self.state
     }
 
     pub fn start_solving(&mut self) -> Result<(), String> {
@@ -356,7 +1074,7 @@ impl Game {
             }
         });
 
-        let result = engine::solve(&self.get_values(), Option::None);
+        let result = engine::solve(&self.variant, &self.get_values(), Option::None);
         if result.is_err() {
             return Err(result.unwrap_err());
         }
@@ -367,6 +1085,12 @@ impl Game {
         });
 
         self.state = GameState::Running;
+        self.history = History::default();
+        self.hints = 0;
+        self.mistakes = 0;
+        self.solver_used = false;
+        self.recorded = false;
+        self.unsaved = true;
         Ok(())
     }
     
@@ -385,7 +1109,8 @@ impl Game {
             solution_count: match request.include_solution_count {
                 true => Option::Some(self.count_solutions(5)),
                 false => Option::None,
-            }
+            },
+            variant: self.variant.clone(),
         };
         request.window.emit("updateGame", event).unwrap();
     }
@@ -398,22 +1123,27 @@ impl Game {
             GameState::Error => {
                 return Err("Conflict detected, can't solve".into())
             },
-            GameState::Editing | GameState::Blank | GameState::Running => {}
+            GameState::Editing => {
+                return Err("Can't solve while editing".into())
+            },
+            GameState::Blank | GameState::Running => {}
         };
 
-        engine::solve(&self.get_values(), Option::None).map(|values| {
-            for row in 0..9 {
-                for col in 0..9 {
-                    let cell = self.cells[row][col].borrow_mut();
+        self.record(|game| engine::solve(&game.variant, &game.get_values(), Option::None).map(|values| {
+            let digits = game.variant.size.digits();
+            for row in 0..digits {
+                for col in 0..digits {
+                    let cell = game.cells[row][col].borrow_mut();
                     let value = values[(row, col)];
                     let _ = cell.set_value(value);
                     cell.solution = Option::Some(value);
                 }
             }
-            self.state = GameState::Solved;
-            request.inspect(|r| self.emit_update_event(r));
-            self.state
-        })
+            game.state = GameState::Solved;
+            game.solver_used = true;
+            request.inspect(|r| game.emit_update_event(r));
+            game.state
+        }))
     }
 
     fn update_states(&mut self, request:Option<&Request>) {
@@ -462,8 +1192,12 @@ impl Game {
             }
         }); 
 
+        self.unsaved = true;
         let new_state:GameState;
-        if has_any_errors {
+        if self.state == GameState::Editing {
+            // conflicts are only flagged on the cells while setting a puzzle
+            new_state = GameState::Editing;
+        } else if has_any_errors {
             new_state = GameState::Error;
         } else if all_set {
             new_state = GameState::Solved;
@@ -476,6 +1210,9 @@ impl Game {
 
         if let Some(req) = request {
             self.emit_update_game_event(req);
+            if self.state == GameState::Editing {
+                let _ = self.editor_status().inspect(|status| req.window.emit("updateEditor", status).unwrap());
+            }
         }
     }
 
@@ -483,13 +1220,14 @@ impl Game {
         if self.state == GameState::Error {
             return 0;
         }
-        engine::count_solutions(&self.get_values(), limit)
+        engine::count_solutions(&self.variant, &self.get_values(), limit)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::engine::{Backend, Cage, Monitor, Symmetry};
 
 
     #[test]
@@ -524,6 +1262,570 @@ mod tests {
         assert!(playfield.is_error(6, 4));
     }
 
+    #[test]
+    fn test_explain_hint() {
+        let mut playfield = Game::new(30, Option::None);
+        let (clues, solution) = engine::generate_monitored(Backend::default(), &Variant::default(), &playfield.get_values(), 42, 30, Symmetry::None, &mut Monitor::unobserved()).unwrap();
+        playfield.install_generated((clues, solution, Option::None), Variant::default(), 30, 42, Option::None, true);
+        let values = playfield.get_values();
+
+        let nudge = playfield.explain_hint(false, Option::None);
+        assert!(nudge.is_ok());
+        assert_eq!(playfield.get_values(), values);
+
+        let (row, col, digit) = playfield.explain_hint(true, Option::None).unwrap().placement().unwrap();
+        assert_eq!(playfield.cells[row][col].cell_state, CellState::Hint);
+        assert_eq!(playfield.cells[row][col].solution, Option::Some(digit));
+        assert_eq!(playfield.get_value(row, col), digit);
+
+        playfield.unhint(Option::None);
+        assert_eq!(playfield.get_values(), values);
+
+        // hints land on blank cells, a wrong entry without conflicts is neither overwritten nor pointed out
+        let candidates = engine::candidates(&playfield.variant, &values).unwrap();
+        let (row, col, wrong) = playfield.cells.iter().flatten()
+            .filter(|cell| cell.value == 0)
+            .find_map(|cell| (1..=9u8)
+                .find(|digit| candidates[(cell.row, cell.col)] & (1 << (digit - 1)) != 0 && cell.solution != Option::Some(*digit))
+                .map(|digit| (cell.row, cell.col, digit)))
+            .unwrap();
+        playfield.set_mistake_check(MistakeCheck::Conflicts, Option::None);
+        let _ = playfield.set_value(wrong, row, col, Option::None);
+        assert!(!playfield.is_error(row, col));
+        let empty = playfield.get_values().elements_row_major_iter().filter(|value| **value == 0).count();
+        let mut result = Ok(());
+        for _ in 0..empty {
+            match playfield.explain_hint(true, Option::None) {
+                Ok(explanation) => {
+                    let (hint_row, hint_col, digit) = explanation.placement().unwrap();
+                    assert_ne!((hint_row, hint_col), (row, col));
+                    assert_eq!(playfield.cells[hint_row][hint_col].solution, Option::Some(digit));
+                },
+                Err(e) => {
+                    result = Err(e);
+                    break;
+                },
+            }
+        }
+        assert_eq!(result, Err("No hint found, the grid contains a mistake".to_string()));
+        assert_eq!(playfield.get_value(row, col), wrong);
+        assert!(!playfield.is_error(row, col));
+    }
+
+    #[test]
+    fn test_undo_redo() {
+        let mut playfield = Game::new(50, Option::None);
+        assert!(playfield.undo(Option::None).is_err());
+
+        let _ = playfield.toggle_note(1, 2, 3, Option::None);
+        let _ = playfield.set_value(1, 1, 2, Option::None);
+        let _ = playfield.set_value(1, 7, 2, Option::None);
+        assert!(playfield.get_state() == GameState::Error);
+        assert!(playfield.cells[1][2].notes.iter().all(|note| !*note));
+
+        assert!(playfield.undo(Option::None).is_ok());
+        assert_eq!(playfield.get_value(7, 2), 0);
+        assert_eq!(playfield.cells[1][2].cell_state, CellState::Set);
+        assert!(playfield.get_state() == GameState::Running);
+
+        assert!(playfield.undo(Option::None).is_ok());
+        assert_eq!(playfield.get_value(1, 2), 0);
+        assert!(playfield.cells[1][2].notes[2]);
+        assert!(playfield.get_state() == GameState::Blank);
+
+        assert!(playfield.redo(Option::None).is_ok());
+        assert_eq!(playfield.get_value(1, 2), 1);
+
+        // a new mutation discards the redo stack
+        let _ = playfield.set_value(5, 0, 0, Option::None);
+        assert!(playfield.redo(Option::None).is_err());
+
+        // history survives serialization
+        let mut restored = Game::from_json(&playfield.to_json().unwrap(), Option::None).unwrap();
+        assert!(restored.undo(Option::None).is_ok());
+        assert_eq!(restored.get_value(0, 0), 0);
+        assert_eq!(restored.get_value(1, 2), 1);
+
+        // only the latest actions are kept
+        for _ in 0..MAX_HISTORY {
+            let _ = restored.toggle_note(8, 8, 1, Option::None);
+        }
+        assert_eq!(restored.history.undo.len(), MAX_HISTORY);
+        assert!(restored.history.undo.iter().all(|action| action.before.iter().all(|cell| (cell.row, cell.col) == (8, 8))));
+    }
+
+    #[test]
+    fn test_fill_candidates() {
+        let puzzle = "003020600900305001001806400008102900700000008006708200002609500800203009005010300";
+        let mut playfield = Game::from_puzzle_string(puzzle, Option::None).unwrap();
+        playfield.fill_candidates(false, Option::None).unwrap();
+        // row 0 holds 2, 3 and 6, col 0 holds 7, 8 and 9, the quad holds 1 and 9 in addition
+        assert_eq!(playfield.cells[0][0].notes, vec![false, false, false, true, true, false, false, false, false]);
+        assert!(playfield.cells[0][2].notes.iter().all(|note| !*note));
+
+        // eliminations are kept in cells with notes, but restored when filling all cells
+        let _ = playfield.toggle_note(0, 0, 4, Option::None);
+        playfield.cells[0][1].notes.iter_mut().for_each(|note| *note = false);
+        playfield.fill_candidates(true, Option::None).unwrap();
+        assert!(!playfield.cells[0][0].notes[3]);
+        assert!(playfield.cells[0][1].notes.contains(&true));
+        playfield.fill_candidates(false, Option::None).unwrap();
+        assert!(playfield.cells[0][0].notes[3]);
+
+        // filling is undone in one step
+        assert!(playfield.undo(Option::None).is_ok());
+        assert!(!playfield.cells[0][0].notes[3]);
+
+        // conflicting entries that aren't flagged yet are ignored as well
+        let notes = playfield.cells[0][1].notes.clone();
+        playfield.set_mistake_check(MistakeCheck::Deferred, Option::None);
+        let _ = playfield.set_value(2, 0, 0, Option::None);
+        assert_eq!(playfield.cells[0][0].cell_state, CellState::Set);
+        playfield.fill_candidates(false, Option::None).unwrap();
+        assert_eq!(playfield.cells[0][1].notes, notes);
+    }
+
+    #[test]
+    fn test_auto_eliminate() {
+        let mut playfield = Game::new(0, Option::None);
+        let _ = playfield.toggle_note(0, 8, 5, Option::None);
+        let _ = playfield.toggle_note(8, 0, 5, Option::None);
+        let _ = playfield.toggle_note(1, 1, 5, Option::None);
+        let _ = playfield.toggle_note(1, 1, 6, Option::None);
+        let _ = playfield.toggle_note(4, 4, 5, Option::None);
+
+        let _ = playfield.set_value(5, 0, 0, Option::None);
+        assert!(playfield.cells[0][8].notes[4]);
+
+        playfield.set_auto_eliminate(true);
+        let _ = playfield.set_value(0, 0, 0, Option::None);
+        let _ = playfield.set_value(5, 0, 0, Option::None);
+        assert!(!playfield.cells[0][8].notes[4]);
+        assert!(!playfield.cells[8][0].notes[4]);
+        assert!(!playfield.cells[1][1].notes[4]);
+        assert!(playfield.cells[1][1].notes[5]);
+        assert!(playfield.cells[4][4].notes[4]);
+
+        // the placement and its eliminations are reverted together
+        assert!(playfield.undo(Option::None).is_ok());
+        assert_eq!(playfield.get_value(0, 0), 0);
+        assert!(playfield.cells[0][8].notes[4]);
+        assert!(playfield.cells[1][1].notes[4]);
+    }
+
+    #[test]
+    fn test_marks_and_colors() {
+        let mut playfield = Game::new(0, Option::None);
+        let _ = playfield.toggle_note(0, 0, 1, Option::None);
+        playfield.toggle_corner_note(0, 0, 2, Option::None).unwrap();
+        assert!(playfield.toggle_corner_note(0, 0, 10, Option::None).is_err());
+        assert!(playfield.cells[0][0].notes[0] && !playfield.cells[0][0].notes[1]);
+        assert!(playfield.cells[0][0].corner_notes[1] && !playfield.cells[0][0].corner_notes[0]);
+
+        playfield.set_color(0, 0, Option::Some(3), Option::None).unwrap();
+        assert!(playfield.set_color(0, 0, Option::Some(10), Option::None).is_err());
+        assert!(playfield.toggle_corner_note(9, 0, 1, Option::None).is_err());
+        assert!(playfield.set_color(0, 9, Option::Some(1), Option::None).is_err());
+        assert!(Game::with_size(Size::new(2, 2).unwrap(), 0, Option::None).set_color(4, 0, Option::Some(1), Option::None).is_err());
+        let _ = playfield.set_value(5, 0, 0, Option::None);
+        assert!(playfield.cells[0][0].corner_notes.iter().all(|note| !*note));
+        assert_eq!(playfield.cells[0][0].color, Option::Some(3));
+
+        let mut restored = Game::from_json(&playfield.to_json().unwrap(), Option::None).unwrap();
+        assert_eq!(restored.cells[0][0].color, Option::Some(3));
+        assert!(restored.undo(Option::None).is_ok());
+        assert!(restored.cells[0][0].corner_notes[1]);
+        assert!(restored.undo(Option::None).is_ok());
+        assert_eq!(restored.cells[0][0].color, Option::None);
+
+        // saves without marks and colours
+        let legacy = serde_json::to_string(&playfield).unwrap();
+        let json = legacy.replace(",\"corner_notes\":[false,false,false,false,false,false,false,false,false],\"color\":null", "");
+        assert_ne!(json, legacy);
+        let restored = Game::from_json(&json, Option::None).unwrap();
+        assert_eq!(restored.cells[1][1].corner_notes.len(), 9);
+        assert!(restored.history.undo.iter().flat_map(|action| action.before.iter()).all(|cell| cell.corner_notes.len() == 9));
+    }
+
+    #[test]
+    fn test_check_notes() {
+        let puzzle = "003020600900305001001806400008102900700000008006708200002609500800203009005010300";
+        let solution = "483921657967345821251876493548132976729564138136798245372689514814253769695417382";
+        for puzzle_string in [puzzle.to_string(), format!("{}\n{}", puzzle, solution)] {
+            let mut playfield = Game::from_puzzle_string(&puzzle_string, Option::None).unwrap();
+            playfield.fill_candidates(false, Option::None).unwrap();
+            assert_eq!(playfield.check_notes(), Ok(vec![]));
+
+            // (0,0) holds a 4, eliminating it dooms the cell
+            let _ = playfield.toggle_note(0, 0, 5, Option::None);
+            assert_eq!(playfield.check_notes(), Ok(vec![]));
+            let _ = playfield.toggle_note(0, 0, 5, Option::None);
+            let _ = playfield.toggle_note(0, 0, 4, Option::None);
+            assert_eq!(playfield.check_notes(), Ok(vec![(0, 0)]));
+        }
+
+        let mut playfield = Game::new(0, Option::None);
+        let _ = playfield.toggle_note(0, 0, 4, Option::None);
+        assert!(playfield.check_notes().is_err());
+    }
+
+    #[test]
+    fn test_mistake_check() {
+        let puzzle = "003020600900305001001806400008102900700000008006708200002609500800203009005010300";
+        let mut playfield = Game::from_puzzle_string(puzzle, Option::None).unwrap();
+
+        // (0,0) holds a 4, a 5 doesn't conflict with the givens
+        let _ = playfield.set_value(5, 0, 0, Option::None);
+        assert_eq!(playfield.cells[0][0].cell_state, CellState::Error);
+        playfield.set_mistake_check(MistakeCheck::Conflicts, Option::None);
+        assert_eq!(playfield.cells[0][0].cell_state, CellState::Set);
+        assert!(playfield.get_state() == GameState::Running);
+        let _ = playfield.set_value(9, 0, 1, Option::None);
+        assert_eq!(playfield.cells[0][1].cell_state, CellState::Error);
+
+        playfield.set_mistake_check(MistakeCheck::Deferred, Option::None);
+        assert_eq!(playfield.cells[0][1].cell_state, CellState::Set);
+        assert!(playfield.get_state() == GameState::Running);
+
+        // the setting is saved with the game
+        let restored = Game::from_json(&playfield.to_json().unwrap(), Option::None).unwrap();
+        assert_eq!(restored.mistake_check, MistakeCheck::Deferred);
+
+        // a full grid is checked against the solution
+        for row in 0..9 {
+            for col in 0..9 {
+                if (row, col) != (0, 0) && playfield.cells[row][col].cell_state != CellState::Fix {
+                    let value = playfield.cells[row][col].solution.unwrap();
+                    let _ = playfield.set_value(value, row, col, Option::None);
+                }
+            }
+        }
+        assert_eq!(playfield.cells[0][0].cell_state, CellState::Error);
+        let _ = playfield.set_value(4, 0, 0, Option::None);
+        assert!(playfield.get_state() == GameState::Solved);
+    }
+
+    #[test]
+    fn test_save_format() {
+        let mut playfield = Game::new(0, Option::None);
+        let _ = playfield.set_value(5, 0, 0, Option::None);
+        let restored = Game::from_json(&playfield.to_json().unwrap(), Option::None).unwrap();
+        assert_eq!(restored.get_value(0, 0), 5);
+
+        // corrupt saves are reported instead of panicking
+        assert!(Game::from_json("", Option::None).is_err());
+        assert!(Game::from_json("{\"cells\": 5}", Option::None).is_err());
+        let newer = "{\"format_version\": 99, \"app_version\": \"9.9.9\", \"saved_at\": 1, \"game\": {}}";
+        assert!(Game::from_json(newer, Option::None).is_err_and(|e| e.contains("9.9.9")));
+
+        // inconsistent saves are rejected as well
+        let valid:serde_json::Value = serde_json::from_str(&playfield.to_json().unwrap()).unwrap();
+        let corrupt = |path:&[&str], value:serde_json::Value| {
+            let mut save = valid.clone();
+            *path.iter().fold(&mut save["game"], |node, key| match key.parse::<usize>() {
+                Ok(idx) => &mut node[idx],
+                Err(_) => &mut node[*key],
+            }) = value;
+            Game::from_json(&save.to_string(), Option::None)
+        };
+        assert!(corrupt(&["cells", "0", "0", "value"], serde_json::json!(5)).is_ok());
+        assert!(corrupt(&["variant", "regions"], serde_json::json!(vec![vec![99; 9]; 9])).is_err());
+        assert!(corrupt(&["cells"], serde_json::json!([])).is_err());
+        assert!(corrupt(&["cells", "0", "1", "row"], serde_json::json!(1)).is_err());
+        assert!(corrupt(&["cells", "0", "0", "value"], serde_json::json!(10)).is_err());
+        assert!(corrupt(&["cells", "0", "0", "notes"], serde_json::json!([true])).is_err());
+        assert!(corrupt(&["cells", "0", "0", "corner_notes"], serde_json::json!([])).is_err());
+        assert!(corrupt(&["history", "undo", "0", "before", "0", "notes"], serde_json::json!([])).is_err());
+        assert!(corrupt(&["history", "undo", "0", "after", "0", "value"], serde_json::json!(12)).is_err());
+    }
+
+    #[test]
+    fn test_unsaved_changes() {
+        let mut playfield = Game::new(0, Option::None);
+        assert!(!playfield.has_unsaved_changes());
+
+        // the timer alone isn't worth an autosave
+        let _ = playfield.set_value(5, 0, 0, Option::None);
+        let _ = playfield.increment_timer();
+        assert!(playfield.has_unsaved_changes());
+        playfield.mark_saved();
+        let _ = playfield.increment_timer();
+        assert!(!playfield.has_unsaved_changes());
+
+        // rejected mutations don't count either
+        assert!(playfield.toggle_note(0, 0, 1, Option::None).is_err());
+        assert!(!playfield.has_unsaved_changes());
+        let _ = playfield.undo(Option::None);
+        assert!(playfield.has_unsaved_changes());
+
+        playfield.mark_saved();
+        playfield.set_mistake_check(MistakeCheck::Conflicts, Option::None);
+        assert!(playfield.has_unsaved_changes());
+        assert!(Game::from_json(&playfield.to_json().unwrap(), Option::None).unwrap().has_unsaved_changes());
+    }
+
+    #[test]
+    fn test_pending_result() {
+        let puzzle = "003020600900305001001806400008102900700000008006708200002609500800203009005010300";
+        let mut playfield = Game::from_puzzle_string(puzzle, Option::None).unwrap();
+        let _ = playfield.set_value(5, 0, 0, Option::None);
+        let _ = playfield.set_value(4, 0, 0, Option::None);
+        playfield.hint(Option::None).unwrap();
+        playfield.unhint(Option::None);
+        let _ = playfield.increment_timer();
+        assert_eq!(playfield.pending_result(), Option::None);
+        assert!(playfield.abandoned_result().unwrap().abandoned);
+
+        for row in 0..9 {
+            for col in 0..9 {
+                if playfield.cells[row][col].value == 0 {
+                    let value = playfield.cells[row][col].solution.unwrap();
+                    let _ = playfield.set_value(value, row, col, Option::None);
+                }
+            }
+        }
+        assert!(playfield.get_state() == GameState::Solved);
+        let record = playfield.pending_result().unwrap();
+        assert_eq!((record.hints, record.mistakes, record.timer_seconds), (1, 1, 1));
+        assert!(record.is_win());
+        assert_eq!(playfield.abandoned_result(), Option::None);
+
+        // the result stays pending until it is recorded, also across saves
+        let restored = Game::from_json(&playfield.to_json().unwrap(), Option::None).unwrap();
+        assert!(restored.pending_result().is_some());
+        playfield.mark_recorded();
+        assert_eq!(playfield.pending_result(), Option::None);
+        let restored = Game::from_json(&playfield.to_json().unwrap(), Option::None).unwrap();
+        assert_eq!(restored.pending_result(), Option::None);
+
+        let mut playfield = Game::from_puzzle_string(puzzle, Option::None).unwrap();
+        playfield.solve(Option::None).unwrap();
+        assert!(!playfield.pending_result().unwrap().is_win());
+
+        // untouched puzzles aren't abandoned, grids without givens aren't puzzles
+        assert_eq!(Game::from_puzzle_string(puzzle, Option::None).unwrap().abandoned_result(), Option::None);
+        let mut playfield = Game::new(0, Option::None);
+        playfield.solve(Option::None).unwrap();
+        assert_eq!(playfield.pending_result(), Option::None);
+    }
+
+    #[test]
+    fn test_puzzle_string() {
+        let puzzle = "003020600900305001001806400008102900700000008006708200002609500800203009005010300";
+        let solution = "483921657967345821251876493548132976729564138136798245372689514814253769695417382";
+
+        let mut playfield = Game::from_puzzle_string(puzzle, Option::None).unwrap();
+        assert!(playfield.get_state() == GameState::Running);
+        assert_eq!(playfield.cells[0][2].cell_state, CellState::Fix);
+        assert_eq!(playfield.cells[0][0].cell_state, CellState::Blank);
+        assert_eq!(playfield.cells[0][0].solution, Option::Some(4));
+        assert!(playfield.set_value(1, 0, 2, Option::None).is_err());
+
+        let _ = playfield.set_value(4, 0, 0, Option::None);
+        let exported = playfield.to_puzzle_string(true).unwrap();
+        assert_eq!(exported, format!("{}\n{}", puzzle.replace("0", "."), solution));
+
+        let restored = Game::from_puzzle_string(&exported, Option::None).unwrap();
+        assert_eq!(restored.to_puzzle_string(false).unwrap(), puzzle.replace("0", "."));
+
+        // grids spread over nine lines, with or without a solution
+        let rows = |grid:&str| grid.as_bytes().chunks(9).map(|row| String::from_utf8_lossy(row).into_owned()).collect::<Vec<String>>().join("\n");
+        let restored = Game::from_puzzle_string(&rows(puzzle), Option::None).unwrap();
+        assert_eq!(restored.to_puzzle_string(false).unwrap(), puzzle.replace("0", "."));
+        let restored = Game::from_puzzle_string(&format!("{}\n\n{}\n", rows(puzzle), rows(solution)), Option::None).unwrap();
+        assert_eq!(restored.to_puzzle_string(true).unwrap(), exported);
+        assert!(Game::from_puzzle_string(&rows(&puzzle[..72]), Option::None).is_err());
+
+        // solution does not match the givens
+        assert!(Game::from_puzzle_string(&format!("{}\n{}", puzzle, solution.replace("4", "x")), Option::None).is_err());
+        assert!(Game::from_puzzle_string(&format!("{}\n{}", puzzle.replacen("0", "8", 1), solution), Option::None).is_err());
+        // multiple solutions
+        assert!(Game::from_puzzle_string(&".".repeat(81), Option::None).is_err());
+    }
+
+    #[test]
+    fn test_redundant_clues() {
+        let puzzle = "003020600900305001001806400008102900700000008006708200002609500800203009005010300";
+        let mut playfield = Game::from_puzzle_string(puzzle, Option::None).unwrap();
+        let redundant = playfield.redundant_clues().unwrap();
+        assert!(!redundant.is_empty());
+
+        // values set while solving are not clues
+        let _ = playfield.set_value(4, 0, 0, Option::None);
+        assert_eq!(playfield.redundant_clues().unwrap(), redundant);
+
+        let (clues, _) = engine::generate_minimal(&Variant::default(), &Array2D::filled_with(0, 9, 9), 42, Symmetry::None).unwrap();
+        let playfield = Game::from_puzzle_string(&engine::format_grid(&clues), Option::None).unwrap();
+        assert_eq!(playfield.redundant_clues(), Ok(vec![]));
+    }
+
+    #[test]
+    fn test_editing() {
+        let puzzle = "003020600900305001001806400008102900700000008006708200002609500800203009005010300";
+        let mut playfield = Game::from_puzzle_string(puzzle, Option::None).unwrap();
+        assert!(playfield.editor_status().is_err());
+
+        // values entered while solving are dropped, the givens become editable
+        let _ = playfield.set_value(4, 0, 0, Option::None);
+        playfield.start_editing(Option::None).unwrap();
+        assert!(playfield.get_state() == GameState::Editing);
+        assert!(playfield.start_editing(Option::None).is_err());
+        assert_eq!(playfield.cells[0][0].cell_state, CellState::Blank);
+        assert_eq!(playfield.cells[0][2].cell_state, CellState::Set);
+        assert_eq!(playfield.cells[0][2].solution, Option::None);
+        let status = playfield.editor_status().unwrap();
+        assert_eq!(status.solution_count, 1);
+        assert_eq!(status.redundant_clues, playfield.redundant_clues().unwrap());
+
+        // solving-only actions are disabled
+        assert!(playfield.hint(Option::None).is_err());
+        assert!(playfield.solve(Option::None).is_err());
+        assert_eq!(playfield.increment_timer(), Ok(0));
+
+        // conflicts are flagged without leaving setter mode
+        let _ = playfield.set_value(3, 0, 0, Option::None);
+        assert_eq!(playfield.cells[0][0].cell_state, CellState::Error);
+        assert!(playfield.get_state() == GameState::Editing);
+        assert_eq!(playfield.editor_status().unwrap().solution_count, 0);
+        assert!(playfield.lock_puzzle(Option::None).is_err());
+
+        let _ = playfield.set_value(0, 0, 0, Option::None);
+        playfield.lock_puzzle(Option::None).unwrap();
+        assert!(playfield.get_state() == GameState::Running);
+        assert_eq!(playfield.cells[0][2].cell_state, CellState::Fix);
+        assert_eq!(playfield.cells[0][0].solution, Option::Some(4));
+        assert!(playfield.stop_editing(Option::None).is_err());
+
+        // a hand-entered grid keeps its values
+        let mut playfield = Game::new(0, Option::None);
+        let _ = playfield.set_value(5, 4, 4, Option::None);
+        playfield.start_editing(Option::None).unwrap();
+        assert_eq!(playfield.get_value(4, 4), 5);
+        assert_eq!(playfield.editor_status().unwrap(), EditorStatus { solution_count: 2, redundant_clues: vec![] });
+        assert!(playfield.lock_puzzle(Option::None).is_err());
+        assert!(playfield.get_state() == GameState::Editing);
+        assert!(playfield.stop_editing(Option::None) == Ok(GameState::Running));
+    }
+
+    #[test]
+    fn test_killer() {
+        let mut playfield = Game::new(0, Option::None);
+        let cages = vec![Cage { cells: vec![(0,0), (1,0)], sum: 3 }];
+        playfield.set_variant(Variant::killer(cages), Option::None).unwrap();
+
+        let _ = playfield.set_value(2, 0, 0, Option::None);
+        assert_eq!(playfield.cells[0][0].cell_state, CellState::Set);
+        let _ = playfield.set_value(3, 1, 0, Option::None);
+        assert_eq!(playfield.cells[1][0].cell_state, CellState::Error);
+        assert!(playfield.get_state() == GameState::Error);
+        let _ = playfield.set_value(1, 1, 0, Option::None);
+        assert_eq!(playfield.cells[1][0].cell_state, CellState::Set);
+
+        let (variant, clues, solution) = engine::generate_killer(&Variant::default(), &Array2D::filled_with(0, 9, 9), 42, Option::Some(60), Symmetry::None).unwrap();
+        let mut playfield = Game::new(60, Option::None);
+        playfield.install_generated((clues, solution, Option::None), variant, 60, 42, Option::None, true);
+        assert!(playfield.get_state() == GameState::Running);
+        assert_eq!(playfield.count_solutions(2), 1);
+        assert!(playfield.set_variant(Variant::default(), Option::None).is_err());
+        assert!(playfield.to_puzzle_string(false).is_err());
+        let (row, col, digit) = playfield.explain_hint(true, Option::None).unwrap().placement().unwrap();
+        assert_eq!(playfield.cells[row][col].solution, Option::Some(digit));
+    }
+
+    #[test]
+    fn test_sizes() {
+        let mut playfield = Game::with_size(Size::new(2, 2).unwrap(), 0, Option::None);
+        assert_eq!(playfield.get_values().num_rows(), 4);
+        assert!(playfield.set_value(5, 0, 0, Option::None).is_err());
+        assert!(playfield.toggle_note(0, 0, 5, Option::None).is_err());
+        assert!(playfield.toggle_note(4, 0, 1, Option::None).is_err());
+
+        let _ = playfield.set_value(1, 0, 0, Option::None);
+        let _ = playfield.set_value(1, 1, 1, Option::None);
+        assert_eq!(playfield.cells[1][1].cell_state, CellState::Error);
+        let _ = playfield.set_value(2, 1, 1, Option::None);
+        assert_eq!(playfield.cells[1][1].cell_state, CellState::Set);
+
+        let variant = Variant::sized(Size::new(2, 3).unwrap());
+        let (clues, solution) = engine::generate_monitored(Backend::default(), &variant, &Array2D::filled_with(0, 6, 6), 42, 18, Symmetry::None, &mut Monitor::unobserved()).unwrap();
+        let mut playfield = Game::new(18, Option::None);
+        playfield.install_generated((clues, solution, Option::None), variant, 18, 42, Option::None, true);
+        assert_eq!(playfield.cells.len(), 6);
+        assert_eq!(playfield.count_solutions(2), 1);
+
+        // other sizes have no logical solver, hints reveal the solution of a blank cell and report wrong entries
+        let (row, col, digit) = playfield.explain_hint(true, Option::None).unwrap().placement().unwrap();
+        assert_eq!(playfield.cells[row][col].cell_state, CellState::Hint);
+        assert_eq!(playfield.cells[row][col].solution, Option::Some(digit));
+        let blank = playfield.cells.iter().flatten().find(|cell| cell.value == 0).unwrap();
+        let (row, col, wrong) = (blank.row, blank.col, blank.solution.unwrap() % 6 + 1);
+        playfield.set_mistake_check(MistakeCheck::Deferred, Option::None);
+        let _ = playfield.set_value(wrong, row, col, Option::None);
+        assert_eq!(playfield.hint(Option::None), Err("No hint found, the grid contains a mistake".into()));
+        assert_eq!(playfield.get_value(row, col), wrong);
+        let _ = playfield.set_value(0, row, col, Option::None);
+        playfield.solve(Option::None).unwrap();
+        assert!(playfield.get_state() == GameState::Solved);
+    }
+
+    #[test]
+    fn test_jigsaw() {
+        // rows as regions: a value repeated in a classic quad is fine, but not within a row
+        let mut playfield = Game::new(0, Option::None);
+        let stripes:Vec<Vec<usize>> = (0..9).map(|r| vec![r; 9]).collect();
+        let mut invalid = stripes.clone();
+        invalid[0][0] = 1;
+        assert!(playfield.set_variant(Variant::jigsaw(invalid), Option::None).is_err());
+        playfield.set_variant(Variant::jigsaw(stripes), Option::None).unwrap();
+
+        let _ = playfield.set_value(5, 0, 0, Option::None);
+        let _ = playfield.set_value(5, 1, 1, Option::None);
+        assert_eq!(playfield.cells[1][1].cell_state, CellState::Set);
+
+        let variant = Variant::jigsaw(engine::generate_regions(Size::default(), 42).unwrap());
+        let (clues, solution) = engine::generate_monitored(Backend::default(), &variant, &Array2D::filled_with(0, 9, 9), 42, 50, Symmetry::None, &mut Monitor::unobserved()).unwrap();
+        let mut playfield = Game::new(50, Option::None);
+        playfield.install_generated((clues, solution, Option::None), variant, 50, 42, Option::None, true);
+        assert_eq!(playfield.count_solutions(2), 1);
+        assert!(playfield.to_puzzle_string(false).is_err());
+    }
+
+    #[test]
+    fn test_extra_units() {
+        let mut playfield = Game::new(0, Option::None);
+        playfield.set_variant(Variant { diagonals: true, windows: true, ..Variant::default() }, Option::None).unwrap();
+
+        let _ = playfield.set_value(1, 0, 0, Option::None);
+        let _ = playfield.set_value(1, 4, 4, Option::None);
+        assert_eq!(playfield.cells[4][4].cell_state, CellState::Error);
+        let _ = playfield.set_value(0, 4, 4, Option::None);
+        let _ = playfield.set_value(2, 1, 7, Option::None);
+        let _ = playfield.set_value(2, 3, 5, Option::None);
+        assert_eq!(playfield.cells[3][5].cell_state, CellState::Error);
+        let _ = playfield.set_value(2, 4, 5, Option::None);
+        assert_eq!(playfield.cells[3][5].cell_state, CellState::Error);
+        let _ = playfield.set_value(0, 3, 5, Option::None);
+        assert_eq!(playfield.cells[4][5].cell_state, CellState::Set);
+        assert!(playfield.get_state() == GameState::Running);
+    }
+
+    #[test]
+    fn test_negative_constraints() {
+        let mut playfield = Game::new(0, Option::None);
+        playfield.set_variant(Variant { anti_knight: true, anti_king: true, non_consecutive: true, ..Variant::default() }, Option::None).unwrap();
+
+        let _ = playfield.set_value(1, 0, 2, Option::None);
+        let _ = playfield.set_value(1, 2, 3, Option::None);
+        assert_eq!(playfield.cells[2][3].cell_state, CellState::Error);
+        let _ = playfield.set_value(0, 2, 3, Option::None);
+        let _ = playfield.set_value(1, 1, 3, Option::None);
+        assert_eq!(playfield.cells[1][3].cell_state, CellState::Error);
+        let _ = playfield.set_value(5, 1, 3, Option::None);
+        assert_eq!(playfield.cells[1][3].cell_state, CellState::Set);
+        let _ = playfield.set_value(6, 1, 4, Option::None);
+        assert_eq!(playfield.cells[1][4].cell_state, CellState::Error);
+    }
+
     #[test]
     fn test_generation() {
 
@@ -531,7 +1833,9 @@ mod tests {
 
         let mut playfield = Game::new(50, Option::None);
         let now = Instant::now();
-        let _ = playfield.generate(58, 42, Option::None, true);
+        let (clues, solution) = engine::generate_monitored(Backend::default(), &Variant::default(), &playfield.get_values(), 42, 58, Symmetry::None, &mut Monitor::unobserved()).unwrap();
+        let rating = logic::rate(&clues).ok();
+        playfield.install_generated((clues, solution, rating), Variant::default(), 58, 42, Option::None, true);
         let elapsed = now.elapsed();
         println!("Elapsed: {:.2?}", elapsed);
     }
@@ -0,0 +1,139 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Serialize, Deserialize};
+use serde_json::Value;
+
+/// Version of the save layout written by this build, bumped with every migration.
+pub const FORMAT_VERSION:u32 = 1;
+
+// Upgrades the game layout by one version
+type Migration = fn(&mut Value) -> Result<(), String>;
+
+// Migrations of the game layout, the one at index `i` upgrades version `i` to `i + 1`
+const MIGRATIONS:[Migration; FORMAT_VERSION as usize] = [
+    migrate_corner_notes,
+];
+
+/// Summary of a saved game, readable without loading the game.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct SaveMetadata {
+    pub difficulty: u8,
+    /// Share of the non-given cells that are filled, in percent.
+    pub progress: u8,
+    /// Elapsed solving time in seconds.
+    pub timer_seconds: u32,
+    /// Current values row by row, one character per cell, see [`crate::engine::format_grid`].
+    pub thumbnail: String,
+}
+
+/// Envelope around a saved game, identifying the layout of the game it contains.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SaveFile {
+    pub format_version: u32,
+    /// Version of the app that wrote the save.
+    pub app_version: String,
+    /// Seconds since the Unix epoch.
+    pub saved_at: u64,
+    /// Missing in saves from before the savegame library.
+    #[serde(default)]
+    pub metadata: Option<SaveMetadata>,
+    pub game: Value,
+}
+
+/// Wraps a game and its summary into an envelope of the current format version.
+pub fn encode<T:Serialize>(game:&T, metadata:SaveMetadata) -> Result<String, serde_json::Error> {
+    let saved_at = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+    serde_json::to_string(&SaveFile {
+        format_version: FORMAT_VERSION,
+        app_version: env!("CARGO_PKG_VERSION").into(),
+        saved_at,
+        metadata: Option::Some(metadata),
+        game: serde_json::to_value(game)?,
+    })
+}
+
+/// Reads an envelope and migrates the game it contains to the current format version.
+/// Saves from before the envelope was introduced are read as version 0.
+/// Saves of newer versions are passed on as they are, their additional fields are ignored.
+pub fn decode(string:&str) -> Result<SaveFile, String> {
+    let value:Value = serde_json::from_str(string).map_err(|e| format!("Invalid save file: {}", e))?;
+    let mut save = match value.get("format_version") {
+        Some(_) => serde_json::from_value::<SaveFile>(value).map_err(|e| format!("Invalid save file: {}", e))?,
+        None => SaveFile {
+            format_version: 0,
+            app_version: String::new(),
+            saved_at: 0,
+            metadata: Option::None,
+            game: value,
+        },
+    };
+
+    while save.format_version < FORMAT_VERSION {
+        MIGRATIONS[save.format_version as usize](&mut save.game)
+            .map_err(|e| format!("Can't migrate save file from version {}: {}", save.format_version, e))?;
+        save.format_version += 1;
+    }
+    Ok(save)
+}
+
+// Version 1 stores corner marks along with the center marks, also in the history
+fn migrate_corner_notes(game:&mut Value) -> Result<(), String> {
+    let game = game.as_object_mut().ok_or("Game is not an object")?;
+    if let Some(rows) = game.get_mut("cells").and_then(|rows| rows.as_array_mut()) {
+        rows.iter_mut().filter_map(|row| row.as_array_mut()).for_each(|row| add_corner_notes(row));
+    }
+    if let Some(history) = game.get_mut("history").and_then(|history| history.as_object_mut()) {
+        history.values_mut()
+            .filter_map(|actions| actions.as_array_mut())
+            .flatten()
+            .filter_map(|action| action.as_object_mut())
+            .flat_map(|action| action.iter_mut())
+            .filter(|(key, _)| *key == "before" || *key == "after")
+            .filter_map(|(_, cells)| cells.as_array_mut())
+            .for_each(|cells| add_corner_notes(cells));
+    }
+    Ok(())
+}
+
+// Adds empty corner marks to the cells without, one for each center mark
+fn add_corner_notes(cells:&mut [Value]) {
+    cells.iter_mut().filter_map(|cell| cell.as_object_mut()).for_each(|cell| {
+        if !cell.contains_key("corner_notes") {
+            let digits = cell.get("notes").and_then(|notes| notes.as_array()).map(|notes| notes.len()).unwrap_or(0);
+            cell.insert("corner_notes".into(), Value::Array(vec![Value::Bool(false); digits]));
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode() {
+        let metadata = SaveMetadata { difficulty: 1, progress: 50, timer_seconds: 3, thumbnail: "12..".into() };
+        let save = decode(&encode(&serde_json::json!({"cells": []}), metadata.clone()).unwrap()).unwrap();
+        assert_eq!(save.format_version, FORMAT_VERSION);
+        assert_eq!(save.metadata, Option::Some(metadata));
+        assert_eq!(save.app_version, env!("CARGO_PKG_VERSION"));
+        assert!(save.saved_at > 0);
+
+        // saves without envelope are migrated from version 0
+        let legacy = r#"{"cells": [[{"notes": [false, true]}]], "history": {"undo": [{"before": [{"notes": [true, false]}], "after": []}], "redo": []}}"#;
+        let save = decode(legacy).unwrap();
+        assert_eq!(save.format_version, FORMAT_VERSION);
+        assert_eq!(save.metadata, Option::None);
+        assert_eq!(save.game["cells"][0][0]["corner_notes"], serde_json::json!([false, false]));
+        assert_eq!(save.game["history"]["undo"][0]["before"][0]["corner_notes"], serde_json::json!([false, false]));
+
+        // newer saves are passed on untouched
+        let newer = r#"{"format_version": 99, "app_version": "9.9.9", "saved_at": 1, "game": {"cells": [[{"notes": []}]]}}"#;
+        let save = decode(newer).unwrap();
+        assert_eq!(save.format_version, 99);
+        assert!(save.game["cells"][0][0].get("corner_notes").is_none());
+
+        assert!(decode("").is_err());
+        assert!(decode("{\"format_version\": 1}").is_err());
+        assert!(decode("[]").is_err());
+    }
+}
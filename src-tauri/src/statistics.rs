@@ -0,0 +1,289 @@
+use std::fs;
+use std::path::Path;
+
+use serde::{Serialize, Deserialize};
+use serde_json::Value;
+
+use crate::engine::{Size, Variant};
+use crate::logic::{Grade, Rating};
+
+/// Version of the statistics layout written by this build, bumped with every migration.
+pub const FORMAT_VERSION:u32 = 1;
+
+// Upgrades the statistics layout by one version
+type Migration = fn(&mut Value) -> Result<(), String>;
+
+// Migrations of the statistics layout, the one at index `i` upgrades version `i + 1` to `i + 2`
+const MIGRATIONS:[Migration; FORMAT_VERSION as usize - 1] = [];
+
+/// Result of a completed or abandoned puzzle.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct GameRecord {
+    pub difficulty: u8,
+    #[serde(default)]
+    pub rating: Option<Rating>,
+    /// Missing in records from before other variants were tracked, which were all classic.
+    #[serde(default)]
+    pub rules: Rules,
+    pub seed: u64,
+    pub timer_seconds: u32,
+    pub hints: u32,
+    pub mistakes: u32,
+    /// The puzzle was finished by the solver, which doesn't count as a win.
+    pub solver_used: bool,
+    /// The puzzle was replaced by another game before it was solved.
+    #[serde(default)]
+    pub abandoned: bool,
+    /// Seconds since the Unix epoch.
+    pub finished_at: u64,
+}
+
+impl GameRecord {
+    pub fn is_win(&self) -> bool {
+        !self.solver_used && !self.abandoned
+    }
+
+    // rated puzzles are grouped by grade, unrated ones by their rules and difficulty
+    fn bucket(&self) -> (Option<Grade>, Option<Rules>, Option<u8>) {
+        match self.rating {
+            Some(rating) => (Option::Some(rating.grade), Option::None, Option::None),
+            None => (Option::None, Option::Some(self.rules), Option::Some(self.difficulty)),
+        }
+    }
+}
+
+/// Rules of a puzzle without its cage and region layouts.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub struct Rules {
+    pub size: Size,
+    pub killer: bool,
+    pub jigsaw: bool,
+    pub diagonals: bool,
+    pub windows: bool,
+    pub anti_knight: bool,
+    pub anti_king: bool,
+    pub non_consecutive: bool,
+}
+
+impl From<&Variant> for Rules {
+    fn from(variant:&Variant) -> Rules {
+        Rules {
+            size: variant.size,
+            killer: !variant.cages.is_empty(),
+            jigsaw: variant.regions.is_some(),
+            diagonals: variant.diagonals,
+            windows: variant.windows,
+            anti_knight: variant.anti_knight,
+            anti_king: variant.anti_king,
+            non_consecutive: variant.non_consecutive,
+        }
+    }
+}
+
+/// Aggregates over the games of one grade, or of one difficulty of unrated puzzles with the same rules.
+/// Times are only taken from wins.
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct DifficultyStatistics {
+    /// `None` for puzzles without a rating.
+    pub grade: Option<Grade>,
+    /// Only set for puzzles without a rating.
+    pub rules: Option<Rules>,
+    /// Only set for puzzles without a rating.
+    pub difficulty: Option<u8>,
+    pub games: u32,
+    pub wins: u32,
+    pub best_seconds: Option<u32>,
+    pub average_seconds: Option<u32>,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct Summary {
+    /// Started games, including the abandoned ones.
+    pub games: u32,
+    pub completed: u32,
+    pub wins: u32,
+    /// Completed games divided by all games, 0 without games.
+    pub completion_rate: f32,
+    /// Wins divided by all completed games, 0 without completed games.
+    pub win_rate: f32,
+    /// Wins in a row up to the last game.
+    pub current_streak: u32,
+    pub best_streak: u32,
+    /// Ordered by grade, unrated puzzles first, ordered by their rules and difficulty.
+    pub difficulties: Vec<DifficultyStatistics>,
+}
+
+/// Results of all completed and abandoned puzzles, in the order they ended.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct Statistics {
+    games: Vec<GameRecord>,
+}
+
+impl Statistics {
+    /// Reads the statistics from `path`, older layouts are migrated. A missing file yields empty statistics.
+    pub fn load(path:&Path) -> Result<Statistics, String> {
+        if !path.exists() {
+            return Ok(Statistics::default());
+        }
+        let content = fs::read_to_string(path).map_err(|e| e.to_string())?;
+        Statistics::from_json(&content)
+    }
+
+    fn from_json(string:&str) -> Result<Statistics, String> {
+        let mut value:Value = serde_json::from_str(string).map_err(|e| format!("Invalid statistics: {}", e))?;
+        let version = value.get("format_version").and_then(|version| version.as_u64()).ok_or("Invalid statistics: no format version")? as u32;
+        if version == 0 || version > FORMAT_VERSION {
+            return Err(format!("Statistics format {} is not supported", version));
+        }
+        for migration in MIGRATIONS[(version - 1) as usize..].iter() {
+            migration(&mut value)?;
+        }
+        serde_json::from_value(value).map_err(|e| format!("Invalid statistics: {}", e))
+    }
+
+    /// Writes the statistics to `path`, replacing the old file only once the new one is complete.
+    pub fn save(&self, path:&Path) -> Result<(), String> {
+        let mut value = serde_json::to_value(self).map_err(|e| e.to_string())?;
+        value["format_version"] = FORMAT_VERSION.into();
+        path.parent().map_or(Ok(()), fs::create_dir_all).map_err(|e| e.to_string())?;
+        let partial = path.with_extension("partial");
+        fs::write(&partial, value.to_string()).map_err(|e| e.to_string())?;
+        fs::rename(partial, path).map_err(|e| e.to_string())
+    }
+
+    pub fn record(&mut self, record:GameRecord) {
+        self.games.push(record);
+    }
+
+    pub fn games(&self) -> &[GameRecord] {
+        &self.games
+    }
+
+    pub fn summary(&self) -> Summary {
+        let games = self.games.len() as u32;
+        let completed = self.games.iter().filter(|game| !game.abandoned).count() as u32;
+        let wins = self.games.iter().filter(|game| game.is_win()).count() as u32;
+        let (current_streak, best_streak) = self.games.iter().fold((0, 0), |(current, best), game| match game.is_win() {
+            true => (current + 1, best.max(current + 1)),
+            false => (0, best),
+        });
+
+        let mut buckets:Vec<(Option<Grade>, Option<Rules>, Option<u8>)> = self.games.iter().map(|game| game.bucket()).collect();
+        buckets.sort();
+        buckets.dedup();
+
+        Summary {
+            games,
+            completed,
+            wins,
+            completion_rate: match games {
+                0 => 0.0,
+                _ => completed as f32 / games as f32,
+            },
+            win_rate: match completed {
+                0 => 0.0,
+                _ => wins as f32 / completed as f32,
+            },
+            current_streak,
+            best_streak,
+            difficulties: buckets.into_iter().map(|bucket| {
+                let games:Vec<&GameRecord> = self.games.iter().filter(|game| game.bucket() == bucket).collect();
+                let times:Vec<u32> = games.iter().filter(|game| game.is_win()).map(|game| game.timer_seconds).collect();
+                let (grade, rules, difficulty) = bucket;
+                DifficultyStatistics {
+                    grade,
+                    rules,
+                    difficulty,
+                    games: games.len() as u32,
+                    wins: times.len() as u32,
+                    best_seconds: times.iter().min().copied(),
+                    average_seconds: match times.len() {
+                        0 => Option::None,
+                        n => Option::Some((times.iter().map(|t| *t as u64).sum::<u64>() / n as u64) as u32),
+                    },
+                }
+            }).collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn game(grade:Grade, timer_seconds:u32, solver_used:bool) -> GameRecord {
+        GameRecord {
+            difficulty: 40,
+            rating: Option::Some(Rating { grade, technique: Option::None, score: 0 }),
+            rules: Rules::default(),
+            seed: 42,
+            timer_seconds,
+            hints: 0,
+            mistakes: 0,
+            solver_used,
+            abandoned: false,
+            finished_at: 0,
+        }
+    }
+
+    #[test]
+    fn test_summary() {
+        let mut statistics = Statistics::default();
+        assert_eq!((statistics.summary().completion_rate, statistics.summary().win_rate), (0.0, 0.0));
+
+        let small = Rules { size: Size::new(2, 2).unwrap(), ..Rules::default() };
+        let unrated = |rules:Rules, difficulty:u8, timer_seconds:u32| GameRecord { rating: Option::None, rules, difficulty, ..game(Grade::Easy, timer_seconds, false) };
+        [
+            game(Grade::Easy, 300, false),
+            game(Grade::Easy, 100, false),
+            game(Grade::Hard, 0, true),
+            game(Grade::Easy, 200, false),
+            unrated(Rules::default(), 40, 50),
+            unrated(small, 10, 30),
+            unrated(Rules::default(), 50, 70),
+            game(Grade::Hard, 500, false),
+            GameRecord { abandoned: true, ..game(Grade::Hard, 20, false) },
+        ].into_iter().for_each(|record| statistics.record(record));
+        let summary = statistics.summary();
+        assert_eq!((summary.games, summary.completed, summary.wins), (9, 8, 7));
+        assert_eq!((summary.completion_rate, summary.win_rate), (8.0 / 9.0, 7.0 / 8.0));
+        // abandoned games break the streak
+        assert_eq!((summary.current_streak, summary.best_streak), (0, 5));
+        // unrated puzzles are told apart by their rules and difficulty
+        let bucket = |grade, rules, difficulty, games, wins, best, average| DifficultyStatistics {
+            grade, rules, difficulty, games, wins, best_seconds: Option::Some(best), average_seconds: Option::Some(average),
+        };
+        assert_eq!(summary.difficulties, vec![
+            bucket(Option::None, Option::Some(small), Option::Some(10), 1, 1, 30, 30),
+            bucket(Option::None, Option::Some(Rules::default()), Option::Some(40), 1, 1, 50, 50),
+            bucket(Option::None, Option::Some(Rules::default()), Option::Some(50), 1, 1, 70, 70),
+            bucket(Option::Some(Grade::Easy), Option::None, Option::None, 3, 3, 100, 200),
+            bucket(Option::Some(Grade::Hard), Option::None, Option::None, 3, 1, 500, 500),
+        ]);
+
+        let killer = Variant { cages: vec![crate::engine::Cage { cells: vec![(0,0)], sum: 1 }], diagonals: true, ..Variant::default() };
+        assert_eq!(Rules::from(&killer), Rules { killer: true, diagonals: true, ..Rules::default() });
+    }
+
+    #[test]
+    fn test_persistence() {
+        let path = std::env::temp_dir().join(format!("sudoku-statistics-{}", std::process::id())).join("statistics.json");
+        assert!(Statistics::load(&path).unwrap().games().is_empty());
+
+        let mut statistics = Statistics::default();
+        statistics.record(game(Grade::Easy, 300, false));
+        statistics.save(&path).unwrap();
+        assert_eq!(Statistics::load(&path).unwrap().games(), statistics.games());
+
+        // unknown fields are ignored, newer layouts aren't overwritten
+        assert!(Statistics::from_json(r#"{"format_version": 1, "games": [], "achievements": []}"#).is_ok());
+        // records from before abandoned games were tracked count as completed
+        let record = r#"{"difficulty": 40, "seed": 42, "timer_seconds": 300, "hints": 0, "mistakes": 0, "solver_used": false, "finished_at": 0}"#;
+        let old = Statistics::from_json(&format!(r#"{{"format_version": 1, "games": [{}]}}"#, record)).unwrap();
+        assert!(!old.games()[0].abandoned);
+        assert!(Statistics::from_json(r#"{"format_version": 99, "games": []}"#).is_err());
+        assert!(Statistics::from_json(r#"{"games": []}"#).is_err());
+        assert!(Statistics::from_json("{").is_err());
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+}
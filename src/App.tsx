@@ -1,14 +1,13 @@
 import "./App.css";
 import MainFrame from "./components/MainFrame";
 import LoadingModal from "./components/LoadModal";
-import { BaseDirectory, readDir } from "@tauri-apps/api/fs";
 import GenerateModal from "./components/GenerateModal";
 import { OpenModal, useStore } from "./store";
 import SaveModal from "./components/SaveModal";
+import { SaveSlot, listSaves } from "./Interface";
 
-const getEntries = async () => {
-  const entries = await readDir("savegames", { dir: BaseDirectory.AppData, recursive: false });
-  return entries;
+const getEntries = () => {
+  return new Promise<SaveSlot[]>((resolve, reject) => listSaves(resolve, reject));
 }
 
 const renderModal = (openModal: number) => {
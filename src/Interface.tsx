@@ -7,12 +7,146 @@ export interface CellUpdateEvent {
     value: number,
     state: number,
     notes: [boolean],
+    corner_notes: [boolean],
+    color: number | null,
+}
+
+export interface Cage {
+    cells: [number, number][],
+    sum: number,
+}
+
+export interface Size {
+    box_rows: number,
+    box_cols: number,
+}
+
+export interface Variant {
+    size: Size,
+    cages: Cage[],
+    regions: number[][] | null,
+    diagonals: boolean,
+    windows: boolean,
+    anti_knight: boolean,
+    anti_king: boolean,
+    non_consecutive: boolean,
 }
 
 export interface GameUpdateEvent {
     state: number,
     clue_count: number,
     solution_count: number,
+    variant: Variant,
+}
+
+export interface SaveMetadata {
+    difficulty: number,
+    progress: number,
+    timer_seconds: number,
+    thumbnail: string,
+}
+
+export interface SaveSlot {
+    name: string,
+    last_played: number,
+    metadata: SaveMetadata | null,
+}
+
+export interface Rules {
+    size: Size,
+    killer: boolean,
+    jigsaw: boolean,
+    diagonals: boolean,
+    windows: boolean,
+    anti_knight: boolean,
+    anti_king: boolean,
+    non_consecutive: boolean,
+}
+
+export interface GameRecord {
+    difficulty: number,
+    rating: Rating | null,
+    rules: Rules,
+    seed: number,
+    timer_seconds: number,
+    hints: number,
+    mistakes: number,
+    solver_used: boolean,
+    abandoned: boolean,
+    finished_at: number,
+}
+
+export interface DifficultyStatistics {
+    grade: string | null,
+    rules: Rules | null,
+    difficulty: number | null,
+    games: number,
+    wins: number,
+    best_seconds: number | null,
+    average_seconds: number | null,
+}
+
+export interface StatisticsSummary {
+    games: number,
+    completed: number,
+    wins: number,
+    completion_rate: number,
+    win_rate: number,
+    current_streak: number,
+    best_streak: number,
+    difficulties: DifficultyStatistics[],
+}
+
+export interface EditorUpdateEvent {
+    solution_count: number,
+    redundant_clues: [number, number][],
+}
+
+export interface HintEvent {
+    technique: string,
+    units: [{[unit:string]: number}],
+    cells: [[number, number]],
+    eliminations: [[number, number, number]],
+    placement: [number, number, number] | null,
+}
+
+export interface GenerateProgressEvent {
+    attempts: number,
+    removed_count: number,
+}
+
+export interface Rating {
+    grade: string,
+    technique: string | null,
+    score: number,
+}
+
+export const Grade = {
+    Easy: 'Easy',
+    Medium: 'Medium',
+    Hard: 'Hard',
+    Extreme: 'Extreme',
+}
+
+export const Symmetry = {
+    None: 'None',
+    Rotational180: 'Rotational180',
+    Rotational90: 'Rotational90',
+    MirrorHorizontal: 'MirrorHorizontal',
+    MirrorVertical: 'MirrorVertical',
+    Diagonal: 'Diagonal',
+    AntiDiagonal: 'AntiDiagonal',
+}
+
+export const Backend = {
+    Backtracking: 'Backtracking',
+    DancingLinks: 'DancingLinks',
+}
+
+export const MistakeCheck = {
+    Solution: 'Solution',
+    Conflicts: 'Conflicts',
+    Deferred: 'Deferred',
 }
 
 export const GameState = {
@@ -51,6 +185,140 @@ export function deserialize(
     }).then(onSuccess).catch(onError)
 }
 
+export function listSaves(
+    onSuccess: (slots:SaveSlot[]) => void,
+    onError: (msg:string) => void
+) {
+    invoke<SaveSlot[]>('list_saves').then(onSuccess).catch(onError);
+}
+
+export function saveGame(
+    name:string,
+    onError: (msg:string) => void
+) {
+    invoke('save_game', {
+        name: name,
+      }).then(_ => {})
+      .catch(onError)
+}
+
+export function loadGame(
+    name:string,
+    includeClueCount:boolean,
+    includeSolutionCount:boolean,
+    onSuccess: (state:number) => void,
+    onError: (msg:string) => void
+) {
+    invoke<number>('load_game', {
+        name: name,
+        includeClueCount: includeClueCount,
+        includeSolutionCount: includeSolutionCount
+    }).then(onSuccess).catch(onError)
+}
+
+export function renameSave(
+    name:string,
+    newName:string,
+    onError: (msg:string) => void
+) {
+    invoke('rename_save', {
+        name: name,
+        newName: newName,
+      }).then(_ => {})
+      .catch(onError)
+}
+
+export function duplicateSave(
+    name:string,
+    newName:string,
+    onError: (msg:string) => void
+) {
+    invoke('duplicate_save', {
+        name: name,
+        newName: newName,
+      }).then(_ => {})
+      .catch(onError)
+}
+
+export function deleteSave(
+    name:string,
+    onError: (msg:string) => void
+) {
+    invoke('delete_save', {
+        name: name,
+      }).then(_ => {})
+      .catch(onError)
+}
+
+export function recoveryInfo(
+    onSuccess: (slot:SaveSlot | null) => void,
+    onError: (msg:string) => void
+) {
+    invoke<SaveSlot | null>('recovery_info').then(onSuccess).catch(onError);
+}
+
+export function restoreRecovery(
+    includeClueCount:boolean,
+    includeSolutionCount:boolean,
+    onSuccess: (state:number) => void,
+    onError: (msg:string) => void
+) {
+    invoke<number>('restore_recovery', {
+        includeClueCount: includeClueCount,
+        includeSolutionCount: includeSolutionCount
+    }).then(onSuccess).catch(onError)
+}
+
+export function discardRecovery(
+    onError: (msg:string) => void
+) {
+    invoke('discard_recovery').then(_ => {}).catch(onError);
+}
+
+export function getStatistics(
+    onSuccess: (summary:StatisticsSummary) => void,
+    onError: (msg:string) => void
+) {
+    invoke<StatisticsSummary>('get_statistics').then(onSuccess).catch(onError);
+}
+
+export function getGameRecords(
+    onSuccess: (records:GameRecord[]) => void,
+    onError: (msg:string) => void
+) {
+    invoke<GameRecord[]>('get_game_records').then(onSuccess).catch(onError);
+}
+
+export function resetStatistics(
+    onError: (msg:string) => void
+) {
+    invoke('reset_statistics').then(_ => {}).catch(onError);
+}
+
+export function importPuzzle(
+    content:string,
+    includeClueCount:boolean,
+    includeSolutionCount:boolean,
+    onSuccess: (state:number) => void,
+    onError: (msg:string) => void
+) {
+    invoke<number>('import_puzzle', {
+        msg:content,
+        includeClueCount: includeClueCount,
+        includeSolutionCount: includeSolutionCount
+    }).then(onSuccess).catch(onError)
+}
+
+export function exportPuzzle(
+    includeSolution:boolean,
+    onSuccess: (value:string) => void,
+    onError: (msg:string) => void
+) {
+    invoke<string>('export_puzzle', {
+        includeSolution: includeSolution,
+    }).then(onSuccess).catch(onError);
+}
+
 export function setCellValue(
     digit:number, 
     row:number, 
@@ -128,18 +396,123 @@ export function generate(
     includeSolutionCount:boolean,
     fixResult:boolean,
     onSuccess: () => void, 
-    onError: (msg:string) => void
+    onError: (msg:string) => void,
+    grade:string | null = null,
+    symmetry:string | null = null,
+    minimal:boolean = false,
+    killer:boolean = false,
+    boxRows:number | null = null,
+    boxCols:number | null = null,
+    jigsaw:boolean = false,
+    diagonals:boolean = false,
+    windows:boolean = false,
+    antiKnight:boolean = false,
+    antiKing:boolean = false,
+    nonConsecutive:boolean = false,
+    backend:string | null = null,
 ) {
     invoke('generate', {
-        difficulty: difficulty,
-        seed: seed,
         includeClueCount: includeClueCount,
         includeSolutionCount: includeSolutionCount,
-        fixResult: fixResult,
+        options: {
+            difficulty: difficulty,
+            seed: seed,
+            fixResult: fixResult,
+            grade: grade,
+            symmetry: symmetry,
+            minimal: minimal,
+            killer: killer,
+            boxRows: boxRows,
+            boxCols: boxCols,
+            jigsaw: jigsaw,
+            diagonals: diagonals,
+            windows: windows,
+            antiKnight: antiKnight,
+            antiKing: antiKing,
+            nonConsecutive: nonConsecutive,
+            backend: backend,
+        },
       }).then(onSuccess)
       .catch(onError)
 }
 
+export function cancelGeneration(
+    onError: (msg:string) => void
+) {
+    invoke('cancel_generation').then(_ => {}).catch(onError);
+}
+
+export function rate(
+    onSuccess: (rating:Rating) => void,
+    onError: (msg:string) => void
+) {
+    invoke<Rating>('rate').then(onSuccess).catch(onError);
+}
+
+export function setVariant(
+    variant:Variant,
+    includeClueCount:boolean,
+    includeSolutionCount:boolean,
+    onError: (msg:string) => void
+) {
+    invoke('set_variant', {
+        variant: variant,
+        includeClueCount: includeClueCount,
+        includeSolutionCount: includeSolutionCount,
+    }).then(_ => {}).catch(onError);
+}
+
+export function setMistakeCheck(
+    mistakeCheck:string,
+    includeClueCount:boolean,
+    includeSolutionCount:boolean,
+    onError: (msg:string) => void
+) {
+    invoke('set_mistake_check', {
+        mistakeCheck: mistakeCheck,
+        includeClueCount: includeClueCount,
+        includeSolutionCount: includeSolutionCount,
+    }).then(_ => {}).catch(onError);
+}
+
+export function redundantClues(
+    onSuccess: (clues:[number, number][]) => void,
+    onError: (msg:string) => void
+) {
+    invoke<[number, number][]>('redundant_clues').then(onSuccess).catch(onError);
+}
+
+export function checkNotes(
+    onSuccess: (cells:[number, number][]) => void,
+    onError: (msg:string) => void
+) {
+    invoke<[number, number][]>('check_notes').then(onSuccess).catch(onError);
+}
+
+export function undo(
+    includeClueCount:boolean,
+    includeSolutionCount:boolean,
+    onError: (msg:string) => void
+) {
+    invoke('undo', {
+        includeClueCount: includeClueCount,
+        includeSolutionCount: includeSolutionCount,
+      }).then(_ => {})
+      .catch(onError)
+}
+
+export function redo(
+    includeClueCount:boolean,
+    includeSolutionCount:boolean,
+    onError: (msg:string) => void
+) {
+    invoke('redo', {
+        includeClueCount: includeClueCount,
+        includeSolutionCount: includeSolutionCount,
+      }).then(_ => {})
+      .catch(onError)
+}
+
 export function triggerUpdate(
     includeClueCount:boolean,
     includeSolutionCount:boolean,
@@ -164,6 +537,50 @@ export function fixResult(
       .catch(onError)
 }
 
+export function startEditing(
+    includeClueCount:boolean,
+    includeSolutionCount:boolean,
+    onError: (msg:string) => void
+) {
+    invoke('start_editing', {
+        includeClueCount: includeClueCount,
+        includeSolutionCount: includeSolutionCount,
+      }).then(_ => {})
+      .catch(onError)
+}
+
+export function stopEditing(
+    includeClueCount:boolean,
+    includeSolutionCount:boolean,
+    onSuccess: (state:number) => void,
+    onError: (msg:string) => void
+) {
+    invoke<number>('stop_editing', {
+        includeClueCount: includeClueCount,
+        includeSolutionCount: includeSolutionCount,
+      }).then(onSuccess)
+      .catch(onError)
+}
+
+export function lockPuzzle(
+    includeClueCount:boolean,
+    includeSolutionCount:boolean,
+    onError: (msg:string) => void
+) {
+    invoke('lock_puzzle', {
+        includeClueCount: includeClueCount,
+        includeSolutionCount: includeSolutionCount,
+      }).then(_ => {})
+      .catch(onError)
+}
+
+export function editorStatus(
+    onSuccess: (status:EditorUpdateEvent) => void,
+    onError: (msg:string) => void
+) {
+    invoke<EditorUpdateEvent>('editor_status').then(onSuccess).catch(onError);
+}
+
 export function toggleNote(
     row:number, col:number, value:number,
     onError: (msg:string) => void
@@ -176,6 +593,50 @@ export function toggleNote(
       .catch(onError)
 }
 
+export function toggleCornerNote(
+    row:number, col:number, value:number,
+    onError: (msg:string) => void
+) {
+    invoke('toggle_corner_note', {
+        row: row,
+        col: col,
+        value: value
+      }).then(_ => {})
+      .catch(onError)
+}
+
+export function setColor(
+    row:number, col:number, color:number | null,
+    onError: (msg:string) => void
+) {
+    invoke('set_color', {
+        row: row,
+        col: col,
+        color: color
+      }).then(_ => {})
+      .catch(onError)
+}
+
+export function fillCandidates(
+    keepNotes:boolean,
+    onError: (msg:string) => void
+) {
+    invoke('fill_candidates', {
+        keepNotes: keepNotes,
+      }).then(_ => {})
+      .catch(onError)
+}
+
+export function setAutoEliminate(
+    enabled:boolean,
+    onError: (msg:string) => void
+) {
+    invoke('set_auto_eliminate', {
+        enabled: enabled,
+      }).then(_ => {})
+      .catch(onError)
+}
+
 export function hint(
     includeClueCount:boolean,
     includeSolutionCount:boolean,
@@ -188,6 +649,20 @@ export function hint(
       .catch(onError)
 }
 
+export function explainHint(
+    reveal:boolean,
+    includeClueCount:boolean,
+    includeSolutionCount:boolean,
+    onError: (msg:string) => void
+) {
+    invoke('explain_hint', {
+        reveal: reveal,
+        includeClueCount: includeClueCount,
+        includeSolutionCount: includeSolutionCount,
+      }).then(_ => {})
+      .catch(onError)
+}
+
 export function unhint(
     includeClueCount:boolean,
     includeSolutionCount:boolean,
@@ -206,4 +681,20 @@ export function onUpdateCell(row:number, col:number, onTrigger: (event:CellUpdat
 
 export function onUpdateGame(onTrigger: (event:GameUpdateEvent) => void): Promise<UnlistenFn> {
     return listen<GameUpdateEvent>('updateGame', event => onTrigger(event.payload));
+}
+
+export function onUpdateEditor(onTrigger: (event:EditorUpdateEvent) => void): Promise<UnlistenFn> {
+    return listen<EditorUpdateEvent>('updateEditor', event => onTrigger(event.payload));
+}
+
+export function onHint(onTrigger: (event:HintEvent) => void): Promise<UnlistenFn> {
+    return listen<HintEvent>('showHint', event => onTrigger(event.payload));
+}
+
+export function onGenerateProgress(onTrigger: (event:GenerateProgressEvent) => void): Promise<UnlistenFn> {
+    return listen<GenerateProgressEvent>('generateProgress', event => onTrigger(event.payload));
+}
+
+export function onStatisticsError(onTrigger: (msg:string) => void): Promise<UnlistenFn> {
+    return listen<string>('statisticsError', event => onTrigger(event.payload));
 }
\ No newline at end of file
@@ -1,30 +1,20 @@
 import { useState } from 'react'
 import Button from './Button'
 import "./Modal.css";
-import { readTextFile, FileEntry } from "@tauri-apps/api/fs";
 import { AppState, OpenModal, useStore } from '../store';
-import { GameState, deserialize } from '../Interface';
-
-const readDataFile = async (path:string, onError:(error:any) => void) => {
-  try {
-    return await readTextFile(path);
-  } catch (e) {
-    onError(e);
-  }
-  return "";
-};
+import { GameState, SaveSlot, loadGame } from '../Interface';
 
 interface Props {
-  promise: Promise<FileEntry[]>;
+  promise: Promise<SaveSlot[]>;
 }
 
 const LoadingModal = ({promise} : Props) => {
-  const [items, setItems] = useState<FileEntry[]>([]);
+  const [items, setItems] = useState<SaveSlot[]>([]);
   const changeOpenModal = useStore(state => state.changeOpenModal);
   const onError = useStore(state => state.changeMessage);
   const changeAppState = useStore(state => state.changeAppState);
   
-  promise.then(i => setItems(i));
+  promise.then(i => setItems(i)).catch(onError);
   const includeCounts = useStore(state => state.appState) == AppState.Editing;
 
   const onSuccess = (state:number) => {
@@ -51,12 +41,10 @@ const LoadingModal = ({promise} : Props) => {
             {
               items.map(i => (
                 <Button
-                  name={i.name || ""}
+                  name={i.metadata ? i.name + ' (' + i.metadata.progress + '%)' : i.name}
                   onClick={() => {
-                    readDataFile(i.path, onError)
-                      .then(content => deserialize(content, includeCounts, includeCounts, onSuccess, onError))
-                      .catch(onError);
-                      
+                    loadGame(i.name, includeCounts, includeCounts, onSuccess, onError);
+
                     changeOpenModal(OpenModal.None);
                   }}
                 />
@@ -1,12 +1,46 @@
+import { useEffect, useState } from 'react';
 import Button from './Button'
 import { AppState, OpenModal, useStore } from '../store';
+import { GameState, SaveSlot, discardRecovery, recoveryInfo, restoreRecovery } from '../Interface';
 
 const StartSidebar = () => {
     const changeOpenModal = useStore(state => state.changeOpenModal);
     const changeAppState = useStore(state => state.changeAppState);
+    const onError = useStore(state => state.changeMessage);
+    const [recovery, setRecovery] = useState<SaveSlot | null>(null);
+
+    useEffect(() => recoveryInfo(setRecovery, onError), []);
+
+    const onRestored = (state:number) => {
+        if (state == GameState.Running) {
+            changeAppState(AppState.Solving);
+        }
+        else if (state == GameState.Solved) {
+            changeAppState(AppState.Solved);
+        }
+        else {
+            changeAppState(AppState.Editing);
+        }
+    }
 
     return (
         <div id='sidebar'>
+            {
+                recovery &&
+                <>
+                    <Button
+                        name={recovery.metadata ? 'resume (' + recovery.metadata.progress + '%)' : 'resume'}
+                        onClick={() => restoreRecovery(false, false, onRestored, onError)}
+                    />
+                    <Button
+                        name='discard'
+                        onClick={() => {
+                            discardRecovery(onError);
+                            setRecovery(null);
+                        }}
+                    />
+                </>
+            }
             <Button
                 name='new game'
                 onClick={() => changeOpenModal(OpenModal.GenerateModal)}